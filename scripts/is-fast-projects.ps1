@@ -16,8 +16,8 @@ function isf_what {
     param (
         [string]$query
     )
-    is-fast `
-        --direct "https://en.wikipedia.org/wiki/$query" `
+    is-fast $query `
+        --engine wikipedia `
         --selector "div.mw-content-ltr > p" `
         --color=always `
         --piped `
@@ -30,7 +30,7 @@ function isf_so {
     param (
         [string]$query
     )
-    $QUESTION = is-fast $query --site "www.stackoverflow.com" --selector "div.question .js-post-body" --color=always --pretty-print="margin:20,title:Question" --piped --flash-cache
+    $QUESTION = is-fast $query --site "www.stackoverflow.com" --engine duckduckgo --selector "div.question .js-post-body" --color=always --pretty-print="margin:20,title:Question" --piped --flash-cache
     $ANSWER = is-fast --last --selector "div.accepted-answer .js-post-body" --color=always --pretty-print="margin:20,title:Answer" --piped --flash-cache
     Write-Output @"
 
@@ -19,12 +19,11 @@ isf_stock() {
         --pretty-print="margin:5"
 }
 
-# What is something? Give it a word or a name and it will return the first wikipedia paragraph of that thing. This will work if there is a wikipedia article with that
-# exact name. Works for most people and things. E.g. isf_what albert einstein
+# What is something? Give it a word or a name and it will return the first wikipedia paragraph of that thing. The wikipedia engine
+# finds the article, so the name doesn't have to be exact. Works for most people and things. E.g. isf_what albert einstein
 isf_what() {
-    local query="${*// /_}"
-    is-fast \
-        --direct "en.wikipedia.org/wiki/${query}" \
+    is-fast ${*} \
+        --engine wikipedia \
         --selector "div.mw-content-ltr > p" \
         --color=always \
         --piped \
@@ -37,7 +36,7 @@ isf_what() {
 
 # Search stack overflow, showing only the question and answer text. Note must use --last for this, as the history output/order is not deterministic.
 isf_so() {
-    QUESTION=$(is-fast ${*} --site "www.stackoverflow.com" --selector "div.question .js-post-body" --color=always --pretty-print="margin:20,title:Question" --piped --flash-cache) # Find the question content.
+    QUESTION=$(is-fast ${*} --site "www.stackoverflow.com" --engine duckduckgo --selector "div.question .js-post-body" --color=always --pretty-print="margin:20,title:Question" --piped --flash-cache) # Find the question content.
     ANSWER=$(is-fast --last --selector "div.accepted-answer .js-post-body" --color=always --pretty-print="margin:20,title:Answer" --piped --flash-cache) # Separately find the answer content.
     cat << EOF # Format as desired
 
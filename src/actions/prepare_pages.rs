@@ -2,38 +2,144 @@ use crate::cli::command::OpenArgs;
 use crate::config::load::Config;
 use crate::database::history_database::get_latest_history;
 use crate::errors::error::IsError;
+use crate::search_engine::domain_filter::DomainFilter;
+use crate::search_engine::feed::force_feed;
 use crate::search_engine::link::HtmlSource::{FileSource, LinkSource};
-use crate::search_engine::link::{File, HtmlSource, Link};
-use crate::search_engine::search::find_links;
+use crate::search_engine::link::{File, HtmlSource, Link, STDIN_PATH};
+use crate::search_engine::search::{find_links, normalize_url};
+use crossterm::tty::IsTty;
 use once_cell::sync::Lazy;
+use std::collections::HashSet;
 
-static SITE: Lazy<Option<String>> = Lazy::new(Config::get_search_site);
+static DOMAIN_FILTER: Lazy<&DomainFilter> = Lazy::new(Config::get_domain_filter);
+static MAX_RESULTS: Lazy<Option<usize>> = Lazy::new(Config::get_max_results);
 
-pub fn prepare_pages(query: OpenArgs) -> Result<Vec<HtmlSource>, IsError> {
+pub fn prepare_pages(query: OpenArgs) -> Result<(Vec<HtmlSource>, Option<ResultPager>), IsError> {
     let mut sources: Vec<HtmlSource> = vec![];
+    let mut pager = None;
     if query.last {
         if let Some(history) = get_latest_history()? {
             sources.push(LinkSource(Link::new(&history.url)));
         }
     }
-    if let Some(file_location) = query.file {
-        sources.push(FileSource(File::new(
-            file_location,
-            query.url.unwrap_or_default(),
-        )));
+    let file_location = query
+        .file
+        .or_else(|| query.stdin.then(|| STDIN_PATH.to_string()));
+    if let Some(file_location) = file_location {
+        let file = File::new(file_location, query.url.unwrap_or_default());
+        // Reading from a terminal would wait forever for input that isn't coming.
+        if file.is_stdin() && std::io::stdin().is_tty() {
+            return Err(IsError::General(String::from(
+                "Nothing was piped to stdin - pipe in the page, such as with curl <url> | is-fast --stdin",
+            )));
+        }
+        sources.push(FileSource(file));
     }
     for url in query.direct {
         sources.push(LinkSource(Link::new(&url)));
     }
+    // Only the pages given are feeds, not the entries that are opened from them.
+    if query.feed {
+        sources.iter().for_each(force_feed);
+    }
     if let Some(search_term) = query.query.map(|q| q.join("+").replace(" ", "+")) {
-        let site = SITE
-            .clone()
-            .map(|s| format!("site:{s}"))
-            .unwrap_or_default();
-        find_links(format!("{search_term}+{site}").trim())?
+        let site = DOMAIN_FILTER.query_terms();
+        let mut result_pager =
+            ResultPager::new(format!("{search_term}+{site}").trim_end_matches('+'));
+        result_pager.add(find_links(&result_pager.search_term, 0)?);
+        // The limit is for the search results, the pages that were given are always opened.
+        sources.extend(result_pager.take(*MAX_RESULTS));
+        pager = Some(result_pager);
+    }
+    Ok((sources, pager))
+}
+
+/// The search that the pages came from, so that more results can be loaded on request. Results
+/// that were cut off by the result limit are used before the next page is fetched from the engine.
+pub struct ResultPager {
+    search_term: String,
+    page: usize,
+    remaining: Vec<Link>,
+    seen: HashSet<String>,
+}
+
+impl ResultPager {
+    fn new(search_term: &str) -> Self {
+        Self {
+            search_term: search_term.to_string(),
+            page: 0,
+            remaining: vec![],
+            seen: HashSet::new(),
+        }
+    }
+
+    pub fn more(&mut self) -> Result<Vec<HtmlSource>, IsError> {
+        if self.remaining.is_empty() {
+            self.page += 1;
+            self.add(find_links(&self.search_term, self.page)?);
+        }
+        if self.remaining.is_empty() {
+            return Err(IsError::Search(String::from("No more results found")));
+        }
+        Ok(self.take(*MAX_RESULTS))
+    }
+
+    // Engines can repeat results across pages, so only new pages are kept.
+    fn add(&mut self, links: Vec<Link>) {
+        let new_links = links
             .into_iter()
-            .map(LinkSource)
-            .for_each(|source| sources.push(source));
+            .filter(|link| self.seen.insert(normalize_url(&link.url)))
+            .collect::<Vec<Link>>();
+        self.remaining.extend(new_links);
+    }
+
+    fn take(&mut self, count: Option<usize>) -> Vec<HtmlSource> {
+        let count = count
+            .unwrap_or(self.remaining.len())
+            .min(self.remaining.len());
+        self.remaining.drain(..count).map(LinkSource).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(sources: &[HtmlSource]) -> Vec<&str> {
+        sources.iter().map(HtmlSource::get_url).collect()
+    }
+
+    #[test]
+    fn test_pager_takes_up_to_limit() {
+        let mut pager = ResultPager::new("rust");
+        pager.add(vec![
+            Link::new("https://www.rust-lang.org"),
+            Link::new("https://docs.rs"),
+            Link::new("https://crates.io"),
+        ]);
+
+        let first = pager.take(Some(2));
+        let second = pager.take(Some(2));
+
+        assert_eq!(
+            urls(&first),
+            vec!["https://www.rust-lang.org", "https://docs.rs"]
+        );
+        assert_eq!(urls(&second), vec!["https://crates.io"]);
+        assert!(pager.take(None).is_empty());
+    }
+
+    #[test]
+    fn test_pager_skips_seen_results() {
+        let mut pager = ResultPager::new("rust");
+        pager.add(vec![Link::new("https://www.rust-lang.org")]);
+        pager.take(None);
+
+        pager.add(vec![
+            Link::new("https://rust-lang.org/"),
+            Link::new("https://docs.rs"),
+        ]);
+
+        assert_eq!(urls(&pager.take(None)), vec!["https://docs.rs"]);
     }
-    Ok(sources)
 }
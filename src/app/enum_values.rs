@@ -1,3 +1,4 @@
+use crate::actions::prepare_pages::ResultPager;
 use crate::app::enum_values::App::{Text, Tui};
 use crate::app::text::TextApp;
 use crate::app::tui::TuiApp;
@@ -26,7 +27,7 @@ pub trait HistoryViewer {
 }
 #[enum_dispatch(App)]
 pub trait PageViewer {
-    fn show_pages(&mut self, pages: &[HtmlSource]);
+    fn show_pages(&mut self, pages: &[HtmlSource], pager: Option<ResultPager>);
 }
 
 #[enum_dispatch(App)]
@@ -40,6 +40,36 @@ pub enum HistoryAction {
     ChangeSearch,
 }
 
+pub fn results_event_loop() -> ResultsAction {
+    if let Ok(event::Event::Key(KeyEvent {
+        code,
+        kind: KeyEventKind::Press,
+        ..
+    })) = event::read()
+    {
+        return match code {
+            KeyCode::Esc => ResultsAction::Exit,
+            KeyCode::Up => ResultsAction::Up,
+            KeyCode::Down => ResultsAction::Down,
+            KeyCode::Enter => ResultsAction::Open,
+            KeyCode::Char(char) => ResultsAction::Text(char),
+            KeyCode::Backspace => ResultsAction::Backspace,
+            _ => ResultsAction::Continue,
+        };
+    }
+    ResultsAction::Continue
+}
+
+pub enum ResultsAction {
+    Exit,
+    Continue,
+    Open,
+    Up,
+    Down,
+    Text(char),
+    Backspace,
+}
+
 pub fn page_event_loop() -> PageAction {
     // As the next page load can take some time especially this can cause an issue if the user
     // enters input while in the loading screen. To fix this we drain the buffer before we read the
@@ -74,5 +104,7 @@ pub(crate) enum PageAction {
     PageDown,
     Next,
     Previous,
+    Results,
+    More,
     Continue,
 }
@@ -2,5 +2,6 @@ pub mod enum_values;
 pub mod event_loop;
 pub mod history;
 mod page;
+mod results;
 pub mod text;
 pub mod tui;
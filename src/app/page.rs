@@ -1,40 +1,122 @@
+use crate::actions::prepare_pages::ResultPager;
 use crate::app::enum_values::PageViewer;
 use crate::app::event_loop::{page_event_loop, PageAction};
 use crate::app::text::TextApp;
 use crate::app::tui::TuiApp;
 use crate::config::load::{Config, Scroll};
 use crate::database::history_database::add_history;
-use crate::search_engine::link::HtmlSource;
+use crate::search_engine::feed::{feed_for, is_forced, Feed};
+use crate::search_engine::link::{HtmlSource, Link};
+use crate::transform::cache::get_content;
 use crate::transform::page::PageExtractor;
 use crate::transform::pretty_print::conditional_formatting;
 use crate::tui::page_content::PageContent;
+use crate::tui::results_content::ResultsContent;
+use std::io;
+use std::io::Write;
 
 impl PageViewer for TuiApp {
-    fn show_pages(&mut self, pages: &[HtmlSource]) {
-        let height = self.display.height() - 2; // Subtract for the border
-        let mut scroll: u16 = 0;
+    fn show_pages(&mut self, pages: &[HtmlSource], mut pager: Option<ResultPager>) {
         if pages.is_empty() {
             self.display.shutdown_with_error("No results found.");
         }
-        let mut index = 0;
-        let mut page_content = PageContent::new(pages, self.display.area());
+        let mut pages = pages.to_vec();
+        if let Some(feed) = self.load_feed(&pages) {
+            let mut entries = feed
+                .entries
+                .into_iter()
+                .map(HtmlSource::LinkSource)
+                .collect::<Vec<HtmlSource>>();
+            let mut results_content =
+                ResultsContent::for_feed(&feed.title, &entries, self.display.area());
+            while let Some(index) = self.pick_result(&mut results_content) {
+                if !self.view_pages(&mut entries, &mut None, index, true) {
+                    break;
+                }
+            }
+        } else if Config::get_result_picker() && pages.len() > 1 {
+            let mut results_content = ResultsContent::new(&pages, self.display.area());
+            let mut result_count = pages.len();
+            while let Some(index) = self.pick_result(&mut results_content) {
+                if !self.view_pages(&mut pages, &mut pager, index, true) {
+                    break;
+                }
+                // More results were loaded from the page, so these need to be in the list.
+                if pages.len() != result_count {
+                    result_count = pages.len();
+                    results_content = ResultsContent::new(&pages, self.display.area());
+                }
+            }
+        } else {
+            self.view_pages(&mut pages, &mut pager, 0, false);
+        }
+        self.display.shutdown();
+    }
+}
+
+impl TuiApp {
+    // Returns true if the user asked to go back to the results list.
+    fn view_pages(
+        &mut self,
+        pages: &mut Vec<HtmlSource>,
+        pager: &mut Option<ResultPager>,
+        start: usize,
+        from_results: bool,
+    ) -> bool {
+        let height = self.display.height() - 2; // Subtract for the border
+        let mut scroll: u16 = 0;
+        let mut index = start;
+        self.loading_page(&pages[index]);
+        let mut page_content = PageContent::new(
+            pages,
+            index,
+            from_results,
+            pager.is_some(),
+            self.display.area(),
+        );
         self.display
             .render(page_content.create_widgets(index, scroll, pages, self.display.area()));
         loop {
             match page_event_loop() {
-                PageAction::Exit => break,
+                PageAction::Exit => return false,
+                PageAction::Results => {
+                    if from_results {
+                        return true;
+                    }
+                    continue;
+                }
+                PageAction::More => {
+                    let Some(pager) = pager.as_mut() else {
+                        continue;
+                    };
+                    self.display.loading();
+                    match pager.more() {
+                        Ok(more) => {
+                            scroll = 0;
+                            index = pages.len();
+                            pages.extend(more);
+                            self.loading_page(&pages[index]);
+                        }
+                        Err(err) => {
+                            log::warn!("Failed to load more results: {err}");
+                            self.display
+                                .loading_page("No more results", &err.to_string());
+                            continue;
+                        }
+                    }
+                }
                 PageAction::Next => {
                     if index < pages.len() - 1 {
-                        self.display.loading();
                         scroll = 0;
                         index += 1;
+                        self.loading_page(&pages[index]);
                     }
                 }
                 PageAction::Previous => {
                     if index > 0 {
-                        self.display.loading();
                         scroll = 0;
                         index -= 1;
+                        self.loading_page(&pages[index]);
                     }
                 }
                 PageAction::Down => {
@@ -66,29 +148,67 @@ impl PageViewer for TuiApp {
                 self.display.area(),
             ));
         }
-        self.display.shutdown();
+    }
+
+    // A single page, or one given with --feed, is loaded first to find whether it is a feed. The
+    // loaded page is kept, so it is not fetched again when it is not.
+    fn load_feed(&mut self, pages: &[HtmlSource]) -> Option<Feed> {
+        let page = pages.first()?;
+        if pages.len() > 1 && !is_forced(page) {
+            return None;
+        }
+        self.loading_page(page);
+        _ = get_content(page);
+        feed_for(page)
+    }
+
+    // Show what the search engine said about the page while it loads.
+    fn loading_page(&mut self, page: &HtmlSource) {
+        match page.get_link() {
+            Some(Link {
+                url,
+                title,
+                snippet,
+                engine: Some(engine),
+                ..
+            }) => {
+                let title = title.as_deref().unwrap_or(url);
+                let description = format!(
+                    "{url} (via {engine})\n\n{}",
+                    snippet.as_deref().unwrap_or_default()
+                );
+                self.display.loading_page(title, &description);
+            }
+            _ => self.display.loading(),
+        }
     }
 }
 
 impl PageViewer for TextApp {
-    fn show_pages(&mut self, pages: &[HtmlSource]) {
+    fn show_pages(&mut self, pages: &[HtmlSource], _pager: Option<ResultPager>) {
         let page_extracter: PageExtractor = PageExtractor::new();
         match pages {
             [page, ..] => {
                 let (title, content) = page_extracter.get_text(page);
-                if let HtmlSource::LinkSource(link) = page {
+                if let HtmlSource::LinkSource(_) = page {
                     if *Config::get_history_enabled() {
-                        let url = &link.url;
-                        add_history(&title, url).unwrap_or_else(|err| {
+                        let url = page.get_final_url();
+                        add_history(&title, &url).unwrap_or_else(|err| {
                             log::error!("Failed to add history for page {title} ({url}) {err}");
                         });
                     }
                 }
                 log::debug!("Outputting page {title} to terminal");
-                println!(
-                    "{}",
-                    conditional_formatting(&title, content, Config::get_pretty_print())
+                let output = conditional_formatting(
+                    &title,
+                    content,
+                    page.get_link(),
+                    Config::get_pretty_print(),
                 );
+                // The reader can stop early, such as head, which is not an error for a filter.
+                writeln!(io::stdout(), "{output}").unwrap_or_else(|err| {
+                    log::debug!("Stopped writing the page {title} to stdout: {err}");
+                });
             }
             [] => eprintln!("No links found, no error detected."),
         }
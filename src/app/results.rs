@@ -0,0 +1,28 @@
+use crate::app::event_loop::{results_event_loop, ResultsAction};
+use crate::app::tui::TuiApp;
+use crate::tui::results_content::ResultsContent;
+
+impl TuiApp {
+    /// Shows the list of search results, returning the index of the page the user picked.
+    pub(crate) fn pick_result(&mut self, results_content: &mut ResultsContent) -> Option<usize> {
+        self.display
+            .render(results_content.create_widgets(self.display.area()));
+        loop {
+            match results_event_loop() {
+                ResultsAction::Continue => continue,
+                ResultsAction::Exit => return None,
+                ResultsAction::Open => {
+                    if let Some(index) = results_content.selected_page() {
+                        return Some(index);
+                    }
+                }
+                ResultsAction::Up => results_content.scroll_up(),
+                ResultsAction::Down => results_content.scroll_down(),
+                ResultsAction::Text(char) => results_content.add_char(char),
+                ResultsAction::Backspace => results_content.remove_char(),
+            }
+            self.display
+                .render(results_content.create_widgets(self.display.area()));
+        }
+    }
+}
@@ -17,6 +17,13 @@ pub enum CacheMode {
     Flash,
 }
 
+#[derive(Debug, PartialEq, Clone, ValueEnum)]
+pub enum SafeSearch {
+    Off,
+    Moderate,
+    Strict,
+}
+
 #[derive(Debug, PartialEq, Clone, ValueEnum, Default)]
 pub enum LogLevel {
     #[default]
@@ -32,25 +39,80 @@ pub struct OpenArgs {
     #[arg(help = "The search query to extract content from websites")]
     pub query: Option<Vec<String>>,
 
-    #[arg(short = 'f', long = "file", help = "Path to the HTML file to render")]
+    #[arg(
+        short = 'f',
+        long = "file",
+        group = "input",
+        help = "Path to the HTML, Markdown, JSON or text file to render, or - to read from stdin"
+    )]
     pub file: Option<String>,
 
+    #[arg(
+        long = "stdin",
+        group = "input",
+        help = "Read the page to render from stdin, the same as --file -"
+    )]
+    pub stdin: bool,
+
     #[arg(
         short = 'u',
         long = "url",
-        requires = "file",
-        help = "Optional URL to associate with the file"
+        requires = "input",
+        help = "Optional URL to associate with the file or stdin"
     )]
     pub url: Option<String>,
 
     #[arg(short = 'd', long = "direct", help = "Open the given URL/s directly")]
     pub direct: Vec<String>,
 
+    #[arg(
+        long = "feed",
+        help = "Show the given URL or file as an RSS or Atom feed, listing its entries"
+    )]
+    pub feed: bool,
+
     #[arg(long, help = "Show last viewed page")]
     pub last: bool,
 
-    #[arg(long = "site", help = "Show results only from a specific site.")]
-    pub site: Option<String>,
+    #[arg(
+        long = "site",
+        allow_hyphen_values = true,
+        help = "Show results only from a specific site. Can be repeated, and sites starting with - are excluded."
+    )]
+    pub site: Vec<String>,
+
+    #[arg(
+        long = "picker",
+        help = "Choose from a list of the search results before opening a page"
+    )]
+    pub picker: bool,
+
+    #[arg(
+        long = "engine",
+        help = "Search with the given engine, overriding the configured engine and fallbacks"
+    )]
+    pub engine: Option<String>,
+
+    #[arg(
+        long = "limit",
+        help = "The maximum number of results to open, more can be loaded from the page"
+    )]
+    pub limit: Option<usize>,
+
+    #[arg(
+        long = "region",
+        help = "The region to search in, as a two letter country code (e.g. gb)"
+    )]
+    pub region: Option<String>,
+
+    #[arg(
+        long = "language",
+        help = "The language to search in, as a two letter language code (e.g. en)"
+    )]
+    pub language: Option<String>,
+
+    #[arg(long = "safe-search", value_enum, help = "Set the safe search level")]
+    pub safe_search: Option<SafeSearch>,
 }
 
 #[derive(Debug, Parser)]
@@ -121,7 +183,7 @@ pub struct OutputArgs {
 
     #[arg(
         long,
-        help = "Additional display configuration when printing to the terminal. Available options: wrap, margin, title"
+        help = "Additional display configuration when printing to the terminal. Available options: wrap, margin, title, result"
     )]
     pub pretty_print: Vec<String>,
 
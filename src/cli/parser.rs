@@ -68,6 +68,7 @@ pub fn parse_pretty_print(line: &str) -> Vec<DisplayConfig> {
                 (Some("title"), some_or_none) => {
                     Some(DisplayConfig::Title(some_or_none.map(ToString::to_string)))
                 }
+                (Some("result"), None) => Some(DisplayConfig::Result),
                 _ => {
                     log::error!("Invalid display configuration: {s}");
                     None
@@ -328,6 +329,12 @@ mod tests {
         assert_eq!(result, vec![DisplayConfig::Title(None)]);
     }
 
+    #[test]
+    fn test_parse_pretty_print_result() {
+        let result = parse_pretty_print("result,wrap");
+        assert_eq!(result, vec![DisplayConfig::Result, DisplayConfig::Wrap]);
+    }
+
     #[test]
     fn test_display_config_multiple_valid_configs() {
         let input = "wrap,margin:15,title:Document";
@@ -1,13 +1,22 @@
 use crate::app::event_loop::PageAction;
-use crate::cli::command::{CacheMode, ColorMode};
+use crate::cli::command::{CacheMode, ColorMode, OpenArgs, SafeSearch};
 use crate::config::color_conversion::{Color, Style};
 use crate::config::files::config_path;
 use crate::config::glob_generation::generate_globs;
 use crate::config::site::{SiteConfig, SitePicker};
-use crate::config::tool_raw::{override_defaults_tool, KeybindingsSection, ToolRawConfig};
+use crate::config::tool_raw::{
+    override_defaults_tool, KeybindingsSection, Names, SearchSection, ToolRawConfig,
+};
 use crate::errors::error::IsError;
+use crate::search_engine::domain_filter::DomainFilter;
+use crate::search_engine::locale::SearchLocale;
 use crate::search_engine::search_type::SearchEngine;
-use crate::search_engine::search_type::SearchEngine::{DuckDuckGo, Google, Kagi};
+use crate::search_engine::search_type::SearchEngine::{
+    DuckDuckGo, Google, Kagi, Local, Searxng, Template, Wikipedia,
+};
+use crate::search_engine::searxng::SearxngSearch;
+use crate::search_engine::template::TemplateSearch;
+use crate::search_engine::wikipedia::WikipediaSearch;
 use crate::DisplayConfig;
 use crossterm::event::{KeyCode, KeyModifiers};
 use globset::{Glob, GlobSet};
@@ -127,7 +136,8 @@ pub struct Config {
     page_margin: u16,
     border_color: Style,
     search_type: AtomKind,
-    search_engine: SearchEngine,
+    search_engines: Result<Vec<SearchEngine>, IsError>,
+    fallback_engines: Vec<SearchEngine>,
     open_tool: Option<Result<Vec<String>, IsError>>,
     scroll: Scroll,
     history_enabled: bool,
@@ -136,38 +146,68 @@ pub struct Config {
     history_widget: HistoryWidgetConfig,
     sites: SitePicker,
     timeout: u64,
-    search_site: Option<String>,
+    domain_filter: DomainFilter,
     keybindings: HashMap<KeyCombo, PageAction>,
+    result_picker: bool,
+    max_results: Option<usize>,
+    search_locale: SearchLocale,
+}
+
+/// The selection settings given on the command line.
+#[derive(Debug, Clone, Default)]
+pub struct SelectionArgs {
+    pub selector: Option<String>,
+    pub ignored: Vec<String>,
+    pub no_block: bool,
+    pub nth_element: Vec<usize>,
+}
+
+/// The search settings given on the command line, which override the configured search.
+#[derive(Debug, Clone, Default)]
+pub struct SearchArgs {
+    pub sites: Vec<String>,
+    pub engine: Option<String>,
+    pub picker: bool,
+    pub limit: Option<usize>,
+    pub region: Option<String>,
+    pub language: Option<String>,
+    pub safe_search: Option<SafeSearch>,
+}
+
+impl From<&OpenArgs> for SearchArgs {
+    fn from(args: &OpenArgs) -> Self {
+        Self {
+            sites: args.site.clone(),
+            engine: args.engine.clone(),
+            picker: args.picker,
+            limit: args.limit,
+            region: args.region.clone(),
+            language: args.language.clone(),
+            safe_search: args.safe_search.clone(),
+        }
+    }
 }
 
 impl Config {
-    // This is where the key configuration is combined, and I would rather have these values being passed
-    // in individually rather than just passing in the arg or combining them into an arbitrary object to appease
-    // clippy. If this grows any larger I will revisit and rework.
-    #[allow(clippy::too_many_arguments)]
+    // This is where the key configuration is combined. The selection and search arguments are
+    // grouped, as each of these is only used to build one part of the config.
     pub fn init(
         args_color_mode: Option<ColorMode>,
         cache_command: Option<&CacheMode>,
         no_history: bool,
         pretty_print: Vec<DisplayConfig>,
-        selector_override: Option<String>,
-        ignored_additional: &[String],
-        no_block: bool,
-        nth_element: Vec<usize>,
+        selection: SelectionArgs,
         styles: &[(String, Style)],
-        search_site: Option<String>,
+        search: SearchArgs,
     ) {
         let this = Self::new(
             args_color_mode,
             cache_command,
             no_history,
             pretty_print,
-            selector_override,
-            ignored_additional,
-            no_block,
-            nth_element,
+            selection,
             styles,
-            search_site,
+            search,
         );
         CONFIG.try_insert(this).expect("Failed to insert config");
     }
@@ -178,30 +218,20 @@ impl Config {
             None,
             false,
             vec![],
-            None,
+            SelectionArgs::default(),
             &[],
-            false,
-            vec![],
-            &[],
-            None,
+            SearchArgs::default(),
         )
     }
 
-    // This is where the key configuration is combined, and I would rather have these values being passed
-    // in individually rather than just passing in the arg or combining them into an arbitrary object to appease
-    // clippy. If this grows any larger I will revisit and rework.
-    #[allow(clippy::too_many_arguments)]
     fn new(
         args_color_mode: Option<ColorMode>,
         cache_mode: Option<&CacheMode>,
         no_history: bool,
         pretty_print: Vec<DisplayConfig>,
-        selector_override: Option<String>,
-        ignored_additional: &[String],
-        no_block: bool,
-        nth_element: Vec<usize>,
+        selection: SelectionArgs,
         styles: &[(String, Style)],
-        search_site: Option<String>,
+        search: SearchArgs,
     ) -> Self {
         let mut tool: ToolRawConfig =
             toml::from_str(DEFAULT_CONFIG).unwrap_or(ToolRawConfig::default());
@@ -209,14 +239,21 @@ impl Config {
             .map(|u_config| override_defaults_tool(&mut tool, u_config));
         let site_picker = SitePicker::new(
             &tool.custom_config,
-            ignored_additional,
-            no_block,
+            &selection.ignored,
+            selection.no_block,
             cache_mode,
             styles,
         );
-        let extraction =
-            Self::create_extraction_config(args_color_mode, selector_override, nth_element, &tool);
+        let extraction = Self::create_extraction_config(
+            args_color_mode,
+            selection.selector,
+            selection.nth_element,
+            &tool,
+        );
         let history_widget = Self::create_history_widget_config(&tool);
+        let domain_filter = Self::create_domain_filter(search.sites, &tool);
+        let search_locale =
+            Self::create_search_locale(search.region, search.language, search.safe_search, &tool);
         let keybind_page: HashMap<KeyCombo, PageAction> = tool
             .keybindings
             .map(create_keybindings)
@@ -241,13 +278,22 @@ impl Config {
                     .and_then(|history| history.search_type.clone())
                     .unwrap_or_default(),
             ),
-            search_engine: to_search_engine(
-                &tool
+            search_engines: match &search.engine {
+                Some(name) => to_arg_search_engine(name, &tool.search.clone().unwrap_or_default())
+                    .map(|engine| vec![engine]),
+                None => Ok(tool
                     .search
                     .as_ref()
-                    .and_then(|search| search.engine.clone())
+                    .map_or_else(|| vec![DuckDuckGo], to_search_engines)),
+            },
+            fallback_engines: match search.engine {
+                Some(_) => vec![], // The engine given as an argument is always used.
+                None => tool
+                    .search
+                    .as_ref()
+                    .map(to_fallback_engines)
                     .unwrap_or_default(),
-            ),
+            },
             open_tool: tool
                 .misc
                 .as_ref()
@@ -273,12 +319,55 @@ impl Config {
             history_widget,
             sites: site_picker,
             timeout: tool.search.as_ref().map_or(4, |search| search.timeout),
-            search_site: search_site
-                .or_else(|| tool.search.as_ref().and_then(|search| search.site.clone())),
+            domain_filter,
             keybindings: keybind_page,
+            result_picker: search.picker
+                || tool
+                    .display
+                    .as_ref()
+                    .and_then(|display| display.result_picker)
+                    .unwrap_or_default(),
+            max_results: search
+                .limit
+                .or_else(|| tool.search.as_ref().and_then(|search| search.max_results))
+                .filter(|max| *max > 0),
+            search_locale,
         }
     }
 
+    // Sites given as arguments replace the configured sites, but the allow and deny lists always apply.
+    fn create_domain_filter(search_sites: Vec<String>, config: &ToolRawConfig) -> DomainFilter {
+        let search = config.search.clone().unwrap_or_default();
+        let sites = if search_sites.is_empty() {
+            search.site.as_ref().map(Names::names).unwrap_or_default()
+        } else {
+            search_sites
+        };
+        DomainFilter::new(
+            &sites,
+            &search.allow_domains.unwrap_or_default(),
+            &search.deny_domains.unwrap_or_default(),
+        )
+    }
+
+    fn create_search_locale(
+        region: Option<String>,
+        language: Option<String>,
+        safe_search: Option<SafeSearch>,
+        config: &ToolRawConfig,
+    ) -> SearchLocale {
+        let search = config.search.as_ref();
+        SearchLocale::new(
+            region.or_else(|| search.and_then(|search| search.region.clone())),
+            language.or_else(|| search.and_then(|search| search.language.clone())),
+            safe_search.or_else(|| {
+                search
+                    .and_then(|search| search.safe_search.as_deref())
+                    .and_then(convert_to_safe_search)
+            }),
+        )
+    }
+
     fn create_history_widget_config(config: &ToolRawConfig) -> HistoryWidgetConfig {
         let title_style = config
             .history
@@ -358,8 +447,12 @@ impl Config {
     pub fn get_search_type() -> &'static AtomKind {
         &Self::get_config().search_type
     }
-    pub fn get_search_engine() -> &'static SearchEngine {
-        &Self::get_config().search_engine
+    pub fn get_search_engines() -> &'static Result<Vec<SearchEngine>, IsError> {
+        &Self::get_config().search_engines
+    }
+
+    pub fn get_fallback_engines() -> &'static [SearchEngine] {
+        &Self::get_config().fallback_engines
     }
 
     pub fn get_open_command() -> Option<&'static Result<Vec<String>, IsError>> {
@@ -390,13 +483,25 @@ impl Config {
         Self::get_config().timeout
     }
 
-    pub fn get_search_site() -> Option<String> {
-        Self::get_config().search_site.clone()
+    pub fn get_domain_filter() -> &'static DomainFilter {
+        &Self::get_config().domain_filter
     }
 
     pub fn get_page_keybinds() -> HashMap<KeyCombo, PageAction> {
         Self::get_config().keybindings.clone()
     }
+
+    pub fn get_result_picker() -> bool {
+        Self::get_config().result_picker
+    }
+
+    pub fn get_max_results() -> Option<usize> {
+        Self::get_config().max_results
+    }
+
+    pub fn get_search_locale() -> &'static SearchLocale {
+        &Self::get_config().search_locale
+    }
 }
 
 fn create_keybindings(keybinds: KeybindingsSection) -> HashMap<KeyCombo, PageAction> {
@@ -410,6 +515,8 @@ fn create_keybindings(keybinds: KeybindingsSection) -> HashMap<KeyCombo, PageAct
         (&keybinds.page_up, PageAction::PageUp),
         (&keybinds.page_down, PageAction::PageDown),
         (&keybinds.open_in_browser, PageAction::Open),
+        (&keybinds.results, PageAction::Results),
+        (&keybinds.more, PageAction::More),
     ];
     for (opt_str, action) in bindings {
         if let Some(s) = opt_str {
@@ -471,6 +578,15 @@ fn convert_to_color_mode(color_mode: &str) -> ColorMode {
     }
 }
 
+fn convert_to_safe_search(safe_search: &str) -> Option<SafeSearch> {
+    match safe_search.to_lowercase().as_str() {
+        "off" => Some(SafeSearch::Off),
+        "moderate" => Some(SafeSearch::Moderate),
+        "strict" => Some(SafeSearch::Strict),
+        _ => None,
+    }
+}
+
 fn convert_to_scroll(scroll: &str) -> Scroll {
     match scroll.to_lowercase().as_str() {
         "full" => Scroll::Full,
@@ -491,15 +607,97 @@ fn to_atom_kind(search_type: &str) -> AtomKind {
     }
 }
 
-fn to_search_engine(search_engine: &str) -> SearchEngine {
-    match search_engine.to_lowercase().as_str() {
-        "duckduckgo" => DuckDuckGo,
-        "google" => Google,
-        "kagi" => Kagi,
-        _ => DuckDuckGo, // Default to duckduckgo
+fn to_search_engines(search: &SearchSection) -> Vec<SearchEngine> {
+    let engines = search
+        .engine
+        .as_ref()
+        .map(Names::names)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|name| to_search_engine(name, search))
+        .collect::<Vec<SearchEngine>>();
+    if engines.is_empty() {
+        vec![DuckDuckGo]
+    } else {
+        engines
     }
 }
 
+fn to_fallback_engines(search: &SearchSection) -> Vec<SearchEngine> {
+    search
+        .fallback
+        .iter()
+        .flatten()
+        .filter_map(|name| to_search_engine(name, search))
+        .collect()
+}
+
+// A mistyped engine in the config is skipped, so the rest of the engines are still searched.
+fn to_search_engine(search_engine: &str, search: &SearchSection) -> Option<SearchEngine> {
+    to_arg_search_engine(search_engine, search)
+        .inspect_err(|err| log::warn!("{err}, skipping it"))
+        .ok()
+}
+
+// Unlike the config, a mistyped engine passed on the command line is reported rather than
+// silently replaced with the default.
+fn to_arg_search_engine(
+    search_engine: &str,
+    search: &SearchSection,
+) -> Result<SearchEngine, IsError> {
+    find_search_engine(search_engine, search).ok_or_else(|| {
+        let built_in = built_in_engines(search);
+        let mut available = built_in.iter().map(SearchEngine::name).collect::<Vec<_>>();
+        let mut custom = search
+            .engines
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        custom.sort_unstable();
+        available.extend(custom);
+        IsError::Search(format!(
+            "Unknown search engine '{search_engine}' - available engines are: {}",
+            available.join(", ")
+        ))
+    })
+}
+
+// The engines that are always available, with the urls set for these in the config.
+fn built_in_engines(search: &SearchSection) -> [SearchEngine; 6] {
+    [
+        DuckDuckGo,
+        Google,
+        Kagi,
+        Local,
+        Searxng(SearxngSearch::new(
+            search.searxng_url.as_deref().unwrap_or_default(),
+        )),
+        Wikipedia(WikipediaSearch::new(search.wikipedia_url.as_deref())),
+    ]
+}
+
+fn find_search_engine(search_engine: &str, search: &SearchSection) -> Option<SearchEngine> {
+    let name = search_engine.to_lowercase();
+    built_in_engines(search)
+        .into_iter()
+        .find(|engine| engine.name() == name)
+        .or_else(|| {
+            search
+                .engines
+                .get(&name)
+                .or_else(|| search.engines.get(search_engine))
+                .map(|engine| {
+                    Template(TemplateSearch::new(
+                        name.clone(),
+                        engine.url.clone(),
+                        engine.selector.clone(),
+                        engine.attribute.clone(),
+                        engine.headers.clone(),
+                    ))
+                })
+        })
+}
+
 fn get_user_specified_tool_config() -> Option<ToolRawConfig> {
     get_user_base_config_file().and_then(|str| {
         toml::from_str(&str)
@@ -521,3 +719,43 @@ pub enum Scroll {
     Half,
     Discrete(u16),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_engines_are_skipped() {
+        let search = SearchSection {
+            engine: Some(Names::Multiple(vec![
+                String::from("gogle"),
+                String::from("wikipedia"),
+            ])),
+            fallback: Some(vec![String::from("bing"), String::from("google")]),
+            ..SearchSection::default()
+        };
+
+        assert!(matches!(to_search_engines(&search)[..], [Wikipedia(_)]));
+        assert!(matches!(to_fallback_engines(&search)[..], [Google]));
+    }
+
+    #[test]
+    fn test_only_unknown_engines_defaults_to_duckduckgo() {
+        let search = SearchSection {
+            engine: Some(Names::Single(String::from("gogle"))),
+            ..SearchSection::default()
+        };
+
+        assert!(matches!(to_search_engines(&search)[..], [DuckDuckGo]));
+    }
+
+    #[test]
+    fn test_unknown_engine_lists_available_engines() {
+        let Err(IsError::Search(message)) = to_arg_search_engine("bing", &SearchSection::default())
+        else {
+            panic!("Expected an unknown engine error");
+        };
+
+        assert!(message.ends_with("duckduckgo, google, kagi, local, searxng, wikipedia"));
+    }
+}
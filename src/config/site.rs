@@ -5,7 +5,17 @@ use crate::config::format::FormatConfig;
 use crate::config::glob_generation::generate_globs;
 use crate::config::load::{get_user_base_config_file, DEFAULT_CONFIG};
 use crate::config::site_raw::{override_defaults_site, CacheSection, SiteRawConfig};
+use crate::search_engine::auth::AuthSettings;
 use crate::search_engine::cache::CacheConfig;
+use crate::search_engine::cookies::CookieSettings;
+use crate::search_engine::redirect::{RedirectSettings, DEFAULT_MAX_HOPS};
+use crate::search_engine::retry::{
+    RetrySettings, DEFAULT_RETRIES, DEFAULT_RETRY_BASE_DELAY, DEFAULT_RETRY_JITTER,
+};
+use crate::search_engine::throttle::{
+    ThrottleSettings, DEFAULT_MAX_CONNECTIONS, DEFAULT_MIN_DELAY,
+};
+use crate::search_engine::tls::TlsSettings;
 use globset::{Glob, GlobSet};
 use once_cell::sync::Lazy;
 use std::collections::{HashMap, HashSet};
@@ -55,13 +65,78 @@ impl SiteConfig {
 
 #[derive(Debug, Clone, Default)]
 pub struct CallConfig {
-    headers: HashMap<String, String>,
+    pub(crate) headers: HashMap<String, String>,
+    pub(crate) proxy: Option<String>,
+    pub(crate) no_proxy: Option<Vec<String>>,
+    pub(crate) tls: TlsSettings,
+    pub(crate) retry: RetrySettings,
+    pub(crate) throttle: ThrottleSettings,
+    pub(crate) cookies: CookieSettings,
+    pub(crate) auth: AuthSettings,
+    pub(crate) redirect: RedirectSettings,
 }
 
 impl CallConfig {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        headers: HashMap<String, String>,
+        proxy: Option<String>,
+        no_proxy: Option<Vec<String>>,
+        tls: TlsSettings,
+        retry: RetrySettings,
+        throttle: ThrottleSettings,
+        cookies: CookieSettings,
+        auth: AuthSettings,
+        redirect: RedirectSettings,
+    ) -> Self {
+        Self {
+            headers,
+            proxy: proxy.filter(|proxy| !proxy.trim().is_empty()),
+            no_proxy,
+            tls,
+            retry,
+            throttle,
+            cookies,
+            auth,
+            redirect,
+        }
+    }
+
     pub fn get_headers(&self) -> &HashMap<String, String> {
         &self.headers
     }
+
+    pub fn get_proxy(&self) -> Option<&str> {
+        self.proxy.as_deref()
+    }
+
+    pub fn get_no_proxy(&self) -> Option<&[String]> {
+        self.no_proxy.as_deref()
+    }
+
+    pub fn get_tls(&self) -> &TlsSettings {
+        &self.tls
+    }
+
+    pub fn get_retry(&self) -> &RetrySettings {
+        &self.retry
+    }
+
+    pub fn get_throttle(&self) -> &ThrottleSettings {
+        &self.throttle
+    }
+
+    pub fn get_cookies(&self) -> &CookieSettings {
+        &self.cookies
+    }
+
+    pub fn get_auth(&self) -> &AuthSettings {
+        &self.auth
+    }
+
+    pub fn get_redirect(&self) -> &RedirectSettings {
+        &self.redirect
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -93,6 +168,15 @@ impl SitePicker {
             .unwrap_or(SiteRawConfig::default());
         _ = get_user_specified_site_config()
             .map(|u_config| override_defaults_site(&mut site, u_config));
+        // Credentials are only sent to the sites they are set for in a custom config, so they
+        // can't leak to every site that is opened.
+        if site.auth.take().is_some_and(|auth| {
+            auth.username.is_some() || auth.password_env.is_some() || auth.token_env.is_some()
+        }) {
+            log::warn!(
+                "[auth] is only used in custom configs for a site, ignoring it in the main config"
+            );
+        }
 
         let base_site_config =
             Self::create_base_site_config(&site, ignored_additional, no_block, cache_mode, styles);
@@ -143,9 +227,82 @@ impl SitePicker {
     }
 
     fn create_call_config(raw: &SiteRawConfig) -> CallConfig {
-        CallConfig {
-            headers: raw.headers.clone(),
-        }
+        let network = raw.network.as_ref();
+        CallConfig::new(
+            raw.headers.clone(),
+            network.and_then(|network| network.proxy.clone()),
+            network.and_then(|network| network.no_proxy.clone()),
+            Self::create_tls_settings(raw),
+            Self::create_retry_settings(raw),
+            Self::create_throttle_settings(raw),
+            Self::create_cookie_settings(raw),
+            Self::create_auth_settings(raw),
+            Self::create_redirect_settings(raw),
+        )
+    }
+
+    fn create_retry_settings(raw: &SiteRawConfig) -> RetrySettings {
+        let default = RetrySettings::default();
+        raw.retry.as_ref().map_or(default, |retry| {
+            RetrySettings::new(
+                retry.retries.unwrap_or(DEFAULT_RETRIES),
+                retry.base_delay.unwrap_or(DEFAULT_RETRY_BASE_DELAY),
+                retry.jitter.unwrap_or(DEFAULT_RETRY_JITTER),
+            )
+        })
+    }
+
+    fn create_throttle_settings(raw: &SiteRawConfig) -> ThrottleSettings {
+        raw.throttle
+            .as_ref()
+            .map_or_else(ThrottleSettings::default, |throttle| {
+                ThrottleSettings::new(
+                    throttle.max_connections.unwrap_or(DEFAULT_MAX_CONNECTIONS),
+                    throttle.min_delay.unwrap_or(DEFAULT_MIN_DELAY),
+                )
+            })
+    }
+
+    fn create_redirect_settings(raw: &SiteRawConfig) -> RedirectSettings {
+        raw.redirect
+            .as_ref()
+            .map_or_else(RedirectSettings::default, |redirect| {
+                RedirectSettings::new(
+                    redirect.max_hops.unwrap_or(DEFAULT_MAX_HOPS),
+                    redirect.follow_canonical.unwrap_or(false),
+                )
+            })
+    }
+
+    fn create_auth_settings(raw: &SiteRawConfig) -> AuthSettings {
+        raw.auth
+            .as_ref()
+            .map_or_else(AuthSettings::default, |auth| {
+                AuthSettings::new(
+                    auth.username.clone(),
+                    auth.password_env.clone(),
+                    auth.token_env.clone(),
+                )
+            })
+    }
+
+    fn create_cookie_settings(raw: &SiteRawConfig) -> CookieSettings {
+        raw.cookies
+            .as_ref()
+            .map_or_else(CookieSettings::default, |cookies| {
+                CookieSettings::new(cookies.store.unwrap_or(false), cookies.import.clone())
+            })
+    }
+
+    fn create_tls_settings(raw: &SiteRawConfig) -> TlsSettings {
+        raw.tls.as_ref().map_or_else(TlsSettings::default, |tls| {
+            TlsSettings::new(
+                tls.ca_bundle.clone(),
+                tls.client_certificate.clone(),
+                tls.client_key.clone(),
+                tls.insecure_skip_verify.unwrap_or(false),
+            )
+        })
     }
 
     fn create_syntax_config(raw: &SiteRawConfig) -> SyntaxConfig {
@@ -36,6 +36,70 @@ pub struct CacheSection {
     pub(crate) ttl: Option<i64>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct NetworkSection {
+    #[serde(default)]
+    pub(crate) proxy: Option<String>,
+    #[serde(default)]
+    pub(crate) no_proxy: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TlsSection {
+    #[serde(default)]
+    pub(crate) ca_bundle: Option<String>,
+    #[serde(default)]
+    pub(crate) client_certificate: Option<String>,
+    #[serde(default)]
+    pub(crate) client_key: Option<String>,
+    #[serde(default)]
+    pub(crate) insecure_skip_verify: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RetrySection {
+    #[serde(default)]
+    pub(crate) retries: Option<u32>,
+    #[serde(default)]
+    pub(crate) base_delay: Option<u64>,
+    #[serde(default)]
+    pub(crate) jitter: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CookiesSection {
+    #[serde(default)]
+    pub(crate) store: Option<bool>,
+    #[serde(default)]
+    pub(crate) import: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuthSection {
+    #[serde(default)]
+    pub(crate) username: Option<String>,
+    #[serde(default)]
+    pub(crate) password_env: Option<String>,
+    #[serde(default)]
+    pub(crate) token_env: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RedirectSection {
+    #[serde(default)]
+    pub(crate) max_hops: Option<usize>,
+    #[serde(default)]
+    pub(crate) follow_canonical: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThrottleSection {
+    #[serde(default)]
+    pub(crate) max_connections: Option<usize>,
+    #[serde(default)]
+    pub(crate) min_delay: Option<u64>,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct SiteRawConfig {
     #[serde(default)]
@@ -48,6 +112,20 @@ pub struct SiteRawConfig {
     pub(crate) cache: Option<CacheSection>,
     #[serde(default)]
     pub(crate) headers: HashMap<String, String>,
+    #[serde(default)]
+    pub(crate) network: Option<NetworkSection>,
+    #[serde(default)]
+    pub(crate) tls: Option<TlsSection>,
+    #[serde(default)]
+    pub(crate) retry: Option<RetrySection>,
+    #[serde(default)]
+    pub(crate) throttle: Option<ThrottleSection>,
+    #[serde(default)]
+    pub(crate) cookies: Option<CookiesSection>,
+    #[serde(default)]
+    pub(crate) auth: Option<AuthSection>,
+    #[serde(default)]
+    pub(crate) redirect: Option<RedirectSection>,
 }
 
 impl SiteRawConfig {
@@ -58,6 +136,13 @@ impl SiteRawConfig {
             syntax: None,
             cache: None,
             headers: HashMap::new(),
+            network: None,
+            tls: None,
+            retry: None,
+            throttle: None,
+            cookies: None,
+            auth: None,
+            redirect: None,
         }
     }
 }
@@ -78,6 +163,25 @@ pub fn override_defaults_site(config: &mut SiteRawConfig, mut u_config: SiteRawC
     for (key, value) in u_config.headers {
         config.headers.insert(key, value);
     }
+    config.network = Some(override_network(
+        config.network.take(),
+        u_config.network.take(),
+    ));
+    config.tls = Some(override_tls(config.tls.take(), u_config.tls.take()));
+    config.retry = Some(override_retry(config.retry.take(), u_config.retry.take()));
+    config.throttle = Some(override_throttle(
+        config.throttle.take(),
+        u_config.throttle.take(),
+    ));
+    config.cookies = Some(override_cookies(
+        config.cookies.take(),
+        u_config.cookies.take(),
+    ));
+    config.auth = Some(override_auth(config.auth.take(), u_config.auth.take()));
+    config.redirect = Some(override_redirect(
+        config.redirect.take(),
+        u_config.redirect.take(),
+    ));
 }
 
 fn override_format(
@@ -149,6 +253,146 @@ fn override_cache(config: Option<CacheSection>, u_config: Option<CacheSection>)
     cache
 }
 
+fn override_network(
+    config: Option<NetworkSection>,
+    u_config: Option<NetworkSection>,
+) -> NetworkSection {
+    let mut network = config.unwrap_or(NetworkSection {
+        proxy: None,
+        no_proxy: None,
+    });
+    if let Some(u_network) = u_config {
+        if let Some(proxy) = u_network.proxy {
+            network.proxy = Some(proxy);
+        }
+        if let Some(no_proxy) = u_network.no_proxy {
+            network.no_proxy = Some(no_proxy);
+        }
+    }
+    network
+}
+
+fn override_tls(config: Option<TlsSection>, u_config: Option<TlsSection>) -> TlsSection {
+    let mut tls = config.unwrap_or(TlsSection {
+        ca_bundle: None,
+        client_certificate: None,
+        client_key: None,
+        insecure_skip_verify: None,
+    });
+    if let Some(u_tls) = u_config {
+        if let Some(ca_bundle) = u_tls.ca_bundle {
+            tls.ca_bundle = Some(ca_bundle);
+        }
+        if let Some(client_certificate) = u_tls.client_certificate {
+            tls.client_certificate = Some(client_certificate);
+        }
+        if let Some(client_key) = u_tls.client_key {
+            tls.client_key = Some(client_key);
+        }
+        if let Some(insecure_skip_verify) = u_tls.insecure_skip_verify {
+            tls.insecure_skip_verify = Some(insecure_skip_verify);
+        }
+    }
+    tls
+}
+
+fn override_retry(config: Option<RetrySection>, u_config: Option<RetrySection>) -> RetrySection {
+    let mut retry = config.unwrap_or(RetrySection {
+        retries: None,
+        base_delay: None,
+        jitter: None,
+    });
+    if let Some(u_retry) = u_config {
+        if let Some(retries) = u_retry.retries {
+            retry.retries = Some(retries);
+        }
+        if let Some(base_delay) = u_retry.base_delay {
+            retry.base_delay = Some(base_delay);
+        }
+        if let Some(jitter) = u_retry.jitter {
+            retry.jitter = Some(jitter);
+        }
+    }
+    retry
+}
+
+fn override_throttle(
+    config: Option<ThrottleSection>,
+    u_config: Option<ThrottleSection>,
+) -> ThrottleSection {
+    let mut throttle = config.unwrap_or(ThrottleSection {
+        max_connections: None,
+        min_delay: None,
+    });
+    if let Some(u_throttle) = u_config {
+        if let Some(max_connections) = u_throttle.max_connections {
+            throttle.max_connections = Some(max_connections);
+        }
+        if let Some(min_delay) = u_throttle.min_delay {
+            throttle.min_delay = Some(min_delay);
+        }
+    }
+    throttle
+}
+
+fn override_cookies(
+    config: Option<CookiesSection>,
+    u_config: Option<CookiesSection>,
+) -> CookiesSection {
+    let mut cookies = config.unwrap_or(CookiesSection {
+        store: None,
+        import: None,
+    });
+    if let Some(u_cookies) = u_config {
+        if let Some(store) = u_cookies.store {
+            cookies.store = Some(store);
+        }
+        if let Some(import) = u_cookies.import {
+            cookies.import = Some(import);
+        }
+    }
+    cookies
+}
+
+fn override_auth(config: Option<AuthSection>, u_config: Option<AuthSection>) -> AuthSection {
+    let mut auth = config.unwrap_or(AuthSection {
+        username: None,
+        password_env: None,
+        token_env: None,
+    });
+    if let Some(u_auth) = u_config {
+        if let Some(username) = u_auth.username {
+            auth.username = Some(username);
+        }
+        if let Some(password_env) = u_auth.password_env {
+            auth.password_env = Some(password_env);
+        }
+        if let Some(token_env) = u_auth.token_env {
+            auth.token_env = Some(token_env);
+        }
+    }
+    auth
+}
+
+fn override_redirect(
+    config: Option<RedirectSection>,
+    u_config: Option<RedirectSection>,
+) -> RedirectSection {
+    let mut redirect = config.unwrap_or(RedirectSection {
+        max_hops: None,
+        follow_canonical: None,
+    });
+    if let Some(u_redirect) = u_config {
+        if let Some(max_hops) = u_redirect.max_hops {
+            redirect.max_hops = Some(max_hops);
+        }
+        if let Some(follow_canonical) = u_redirect.follow_canonical {
+            redirect.follow_canonical = Some(follow_canonical);
+        }
+    }
+    redirect
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -171,6 +415,13 @@ mod tests {
             }),
             cache: None,
             headers: Default::default(),
+            network: None,
+            tls: None,
+            retry: None,
+            throttle: None,
+            cookies: None,
+            auth: None,
+            redirect: None,
         };
 
         let user_config = SiteRawConfig {
@@ -189,6 +440,13 @@ mod tests {
             }),
             cache: None,
             headers: Default::default(),
+            network: None,
+            tls: None,
+            retry: None,
+            throttle: None,
+            cookies: None,
+            auth: None,
+            redirect: None,
         };
 
         override_defaults_site(&mut default_config, user_config);
@@ -11,6 +11,8 @@ pub struct DisplaySection {
     pub(crate) scroll: Option<String>,
     #[serde(default)]
     pub(crate) color_mode: Option<String>,
+    #[serde(default)]
+    pub(crate) result_picker: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -29,14 +31,61 @@ pub struct HistorySection {
     pub(crate) enabled: Option<bool>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct SearchSection {
     #[serde(default)]
-    pub(crate) engine: Option<String>,
+    pub(crate) engine: Option<Names>,
+    #[serde(default)]
+    pub(crate) fallback: Option<Vec<String>>,
+    #[serde(default)]
+    pub(crate) site: Option<Names>,
+    #[serde(default)]
+    pub(crate) allow_domains: Option<Vec<String>>,
+    #[serde(default)]
+    pub(crate) deny_domains: Option<Vec<String>>,
+    #[serde(default)]
+    pub(crate) max_results: Option<usize>,
     #[serde(default)]
-    pub(crate) site: Option<String>,
+    pub(crate) region: Option<String>,
+    #[serde(default)]
+    pub(crate) language: Option<String>,
+    #[serde(default)]
+    pub(crate) safe_search: Option<String>,
     #[serde(default)]
     pub(crate) timeout: u64,
+    #[serde(default)]
+    pub(crate) searxng_url: Option<String>,
+    #[serde(default)]
+    pub(crate) wikipedia_url: Option<String>,
+    #[serde(default)]
+    pub(crate) engines: HashMap<String, EngineSection>,
+}
+
+// Values such as the engine and site can be given as a single name, or as a list of names.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Names {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl Names {
+    pub fn names(&self) -> Vec<String> {
+        match self {
+            Names::Single(name) => vec![name.clone()],
+            Names::Multiple(names) => names.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct EngineSection {
+    pub(crate) url: String,
+    pub(crate) selector: String,
+    #[serde(default)]
+    pub(crate) attribute: Option<String>,
+    #[serde(default)]
+    pub(crate) headers: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -65,6 +114,10 @@ pub struct KeybindingsSection {
     pub(crate) page_down: Option<String>,
     #[serde(default)]
     pub(crate) open_in_browser: Option<String>,
+    #[serde(default)]
+    pub(crate) results: Option<String>,
+    #[serde(default)]
+    pub(crate) more: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -134,6 +187,7 @@ fn override_display(
         page_margin: None,
         scroll: None,
         color_mode: None,
+        result_picker: None,
     });
     if let Some(u_display) = u_config {
         if let Some(border_color) = u_display.border_color {
@@ -150,6 +204,9 @@ fn override_display(
         if let Some(color_mode) = u_display.color_mode {
             display.color_mode = Some(color_mode);
         }
+        if let Some(result_picker) = u_display.result_picker {
+            display.result_picker = Some(result_picker);
+        }
     }
     display
 }
@@ -196,20 +253,60 @@ fn override_search(
 ) -> SearchSection {
     let mut search = config.unwrap_or(SearchSection {
         engine: None,
+        fallback: None,
         site: None,
+        allow_domains: None,
+        deny_domains: None,
+        max_results: None,
+        region: None,
+        language: None,
+        safe_search: None,
         timeout: 0,
+        searxng_url: None,
+        wikipedia_url: None,
+        engines: HashMap::new(),
     });
 
     if let Some(u_search) = u_config {
         if let Some(engine) = u_search.engine {
             search.engine = Some(engine);
         }
+        if let Some(fallback) = u_search.fallback {
+            search.fallback = Some(fallback);
+        }
         if let Some(site) = u_search.site {
             search.site = Some(site);
         }
+        if let Some(allow_domains) = u_search.allow_domains {
+            search.allow_domains = Some(allow_domains);
+        }
+        if let Some(deny_domains) = u_search.deny_domains {
+            search.deny_domains = Some(deny_domains);
+        }
+        if let Some(max_results) = u_search.max_results {
+            search.max_results = Some(max_results);
+        }
+        if let Some(region) = u_search.region {
+            search.region = Some(region);
+        }
+        if let Some(language) = u_search.language {
+            search.language = Some(language);
+        }
+        if let Some(safe_search) = u_search.safe_search {
+            search.safe_search = Some(safe_search);
+        }
         if u_search.timeout > 0 {
             search.timeout = u_search.timeout;
         }
+        if let Some(searxng_url) = u_search.searxng_url {
+            search.searxng_url = Some(searxng_url);
+        }
+        if let Some(wikipedia_url) = u_search.wikipedia_url {
+            search.wikipedia_url = Some(wikipedia_url);
+        }
+        for (name, engine) in u_search.engines {
+            search.engines.insert(name, engine);
+        }
     }
     search
 }
@@ -227,6 +324,8 @@ fn override_keybinds_page(
         page_up: None,
         page_down: None,
         open_in_browser: None,
+        results: None,
+        more: None,
     });
     if let Some(ukps) = u_keybind_page_section {
         if let Some(exit) = ukps.exit {
@@ -253,6 +352,12 @@ fn override_keybinds_page(
         if let Some(open_in_browser) = ukps.open_in_browser {
             kps.open_in_browser = Some(open_in_browser);
         }
+        if let Some(results) = ukps.results {
+            kps.results = Some(results);
+        }
+        if let Some(more) = ukps.more {
+            kps.more = Some(more);
+        }
     }
     kps
 }
@@ -290,6 +395,7 @@ mod tests {
                 page_margin: Some(3),
                 scroll: None,
                 color_mode: None,
+                result_picker: None,
             }),
             history: Some(HistorySection {
                 title_color: Some("blue".to_string()),
@@ -316,6 +422,7 @@ mod tests {
                 page_margin: Some(5),
                 scroll: None,
                 color_mode: None,
+                result_picker: None,
             }),
             history: Some(HistorySection {
                 title_color: Some("red".to_string()),
@@ -370,4 +477,56 @@ mod tests {
             Some("fuzzy".to_string())
         );
     }
+
+    #[test]
+    fn test_override_search_engines() {
+        let mut default_config: ToolRawConfig = toml::from_str(
+            r#"
+            [search]
+            engine = "duckduckgo"
+
+            [search.engines.mdn]
+            url = "https://developer.mozilla.org/en-US/search?q={query}"
+            selector = "a.result"
+            "#,
+        )
+        .unwrap();
+        let user_config: ToolRawConfig = toml::from_str(
+            r#"
+            [search]
+            engine = "docs"
+
+            [search.engines.docs]
+            url = "https://docs.rs/releases/search?query={query}"
+            selector = "a.release"
+            headers = { "Accept" = "text/html" }
+            "#,
+        )
+        .unwrap();
+
+        override_defaults_tool(&mut default_config, user_config);
+
+        let search = default_config.search.unwrap();
+        assert_eq!(search.engine, Some(Names::Single("docs".to_string())));
+        assert_eq!(search.engines.len(), 2);
+        assert_eq!(search.engines["docs"].selector, "a.release");
+        assert_eq!(search.engines["docs"].headers["Accept"], "text/html");
+        assert_eq!(search.engines["mdn"].attribute, None);
+    }
+
+    #[test]
+    fn test_search_engine_list() {
+        let config: ToolRawConfig = toml::from_str(
+            r#"
+            [search]
+            engine = ["duckduckgo", "searxng"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.search.unwrap().engine.unwrap().names(),
+            vec!["duckduckgo".to_string(), "searxng".to_string()]
+        );
+    }
 }
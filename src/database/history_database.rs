@@ -89,9 +89,32 @@ pub fn get_latest_history() -> Result<Option<HistoryData>, IsError> {
     }
 }
 
+// The search index reads from the history table, and is rebuilt before each search as the
+// history is small.
+pub fn search_history(query: &str) -> Result<Vec<HistoryData>, IsError> {
+    let conn = CONNECTION.lock().map_err(|e| Access(e.to_string()))?;
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS history_search USING fts5(
+             title, url, content='history'
+         );
+         INSERT INTO history_search(history_search) VALUES('rebuild');",
+    )?;
+    let mut stmt = conn.prepare(
+        "SELECT history.title, history.url, history.time FROM history_search
+         JOIN history ON history.rowid = history_search.rowid
+         WHERE history_search MATCH ? ORDER BY rank",
+    )?;
+    let history: Vec<HistoryData> = stmt
+        .query_map([query], convert_to_history_data)?
+        .collect::<Result<_, _>>()
+        .map_err(DatabaseSql)?;
+    log::debug!("Found {} history items matching {query}", history.len());
+    Ok(history)
+}
+
 pub fn clear_history() -> Result<(), IsError> {
     let conn = CONNECTION.lock().map_err(|e| Access(e.to_string()))?;
-    conn.execute("DROP TABLE history", [])?;
+    conn.execute_batch("DROP TABLE history; DROP TABLE IF EXISTS history_search;")?;
     Ok(())
 }
 
@@ -25,7 +25,7 @@ use crate::cli::parser::{
     determine_cache_mode, determine_ignored, determine_nth_element, parse_pretty_print,
 };
 use crate::config::color_conversion::Style;
-use crate::config::load::Config;
+use crate::config::load::{Config, SearchArgs, SelectionArgs};
 use crate::database::history_database;
 use crate::errors::error::IsError;
 use crate::search_engine::cache;
@@ -40,6 +40,7 @@ enum DisplayConfig {
     Margin(u16),
     Wrap,
     Title(Option<String>),
+    Result,
 }
 
 fn main() {
@@ -47,20 +48,21 @@ fn main() {
     init_logger(args.log);
     let pretty_print = parse_pretty_print(&args.output.pretty_print.join(","));
     let cache_command = determine_cache_mode(&args.cache);
-    let ignored = determine_ignored(args.selection.ignore);
-    let nth_element = determine_nth_element(args.selection.nth_element);
+    let selection = SelectionArgs {
+        selector: args.selection.selector.clone(),
+        ignored: determine_ignored(args.selection.ignore),
+        no_block: args.selection.no_block,
+        nth_element: determine_nth_element(args.selection.nth_element),
+    };
     let styles = determine_styles(args.output.style_element);
     Config::init(
         args.output.color.clone(),
         cache_command.as_ref(),
         args.history.no_history,
         pretty_print,
-        args.selection.selector.clone(),
-        &ignored,
-        args.selection.no_block,
-        nth_element,
+        selection,
         &styles,
-        args.open.site.clone(),
+        SearchArgs::from(&args.open),
     );
     // Generate config doesn't need a display, process and return.
     if args.task.generate_config {
@@ -79,13 +81,13 @@ fn main() {
     app.loading();
     if args.history.history {
         if let Some(page) = app.show_history() {
-            app.show_pages(&[page]);
+            app.show_pages(&[page], None);
         }
     } else {
-        let page_result = prepare_pages(args.open).unwrap_or_else(|err| {
+        let (page_result, pager) = prepare_pages(args.open).unwrap_or_else(|err| {
             app.shutdown_with_error(&err.to_string());
         });
-        app.show_pages(&page_result);
+        app.show_pages(&page_result, pager);
     }
     app.shutdown();
 }
@@ -0,0 +1,143 @@
+use crate::errors::error::IsError;
+use crate::errors::error::IsError::Scrape;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::env;
+
+/// The credentials to send to a site. Secrets are read from the named environment variables when
+/// the request is made, so they are never kept in the config.
+#[derive(Debug, Clone, Default)]
+pub enum AuthSettings {
+    #[default]
+    None,
+    Basic {
+        username: String,
+        password_env: String,
+    },
+    Bearer {
+        token_env: String,
+    },
+}
+
+impl AuthSettings {
+    pub fn new(
+        username: Option<String>,
+        password_env: Option<String>,
+        token_env: Option<String>,
+    ) -> Self {
+        match (username, password_env, token_env) {
+            (username, _, Some(token_env)) => {
+                if username.is_some() {
+                    log::warn!("Both a username and token_env are set in [auth], using the token");
+                }
+                Self::Bearer { token_env }
+            }
+            (Some(username), Some(password_env), None) => Self::Basic {
+                username,
+                password_env,
+            },
+            (Some(_), None, None) => {
+                log::warn!("No password_env is set for the username in [auth], so it is not used");
+                Self::None
+            }
+            (None, _, None) => Self::None,
+        }
+    }
+}
+
+/// Creates the Authorization header value for the url, or None when the site has no credentials.
+pub fn auth_header(url: &str, settings: &AuthSettings) -> Result<Option<String>, IsError> {
+    resolve_auth_header(url, settings, |name| env::var(name).ok())
+}
+
+fn resolve_auth_header(
+    url: &str,
+    settings: &AuthSettings,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<Option<String>, IsError> {
+    let secret = |name: &str| {
+        env(name).ok_or_else(|| {
+            Scrape(format!(
+                "Unable to get the environment variable {name} for the credentials of {url}"
+            ))
+        })
+    };
+    match settings {
+        AuthSettings::None => Ok(None),
+        AuthSettings::Basic {
+            username,
+            password_env,
+        } => {
+            let password = secret(password_env)?;
+            log::debug!("Using basic auth as {username} for {url}");
+            Ok(Some(format!(
+                "Basic {}",
+                STANDARD.encode(format!("{username}:{password}"))
+            )))
+        }
+        AuthSettings::Bearer { token_env } => {
+            let token = secret(token_env)?;
+            log::debug!("Using a bearer token for {url}");
+            Ok(Some(format!("Bearer {}", token.trim())))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(name: &str) -> Option<String> {
+        match name {
+            "WIKI_PASSWORD" => Some(String::from("open sesame")),
+            "GITLAB_TOKEN" => Some(String::from("glpat-123\n")),
+            _ => None,
+        }
+    }
+
+    fn header(settings: AuthSettings) -> Result<Option<String>, IsError> {
+        resolve_auth_header("https://wiki.internal", &settings, env)
+    }
+
+    #[test]
+    fn test_basic_auth_header() {
+        let settings = AuthSettings::new(
+            Some(String::from("aladdin")),
+            Some(String::from("WIKI_PASSWORD")),
+            None,
+        );
+
+        assert_eq!(
+            header(settings).unwrap(),
+            Some(String::from("Basic YWxhZGRpbjpvcGVuIHNlc2FtZQ=="))
+        );
+    }
+
+    #[test]
+    fn test_bearer_auth_header() {
+        let settings = AuthSettings::new(None, None, Some(String::from("GITLAB_TOKEN")));
+
+        assert_eq!(
+            header(settings).unwrap(),
+            Some(String::from("Bearer glpat-123"))
+        );
+    }
+
+    #[test]
+    fn test_missing_environment_variable_errors() {
+        let settings = AuthSettings::new(None, None, Some(String::from("MISSING_TOKEN")));
+
+        assert!(
+            matches!(header(settings), Err(Scrape(message)) if message.contains("MISSING_TOKEN"))
+        );
+    }
+
+    #[test]
+    fn test_no_credentials() {
+        assert_eq!(header(AuthSettings::default()).unwrap(), None);
+        assert_eq!(
+            header(AuthSettings::new(Some(String::from("me")), None, None)).unwrap(),
+            None
+        );
+    }
+}
@@ -2,10 +2,13 @@ use crate::cli::command::CacheMode;
 use crate::config::files::database_path;
 use crate::errors::error::IsError;
 use crate::search_engine::link::HtmlSource;
+use crate::search_engine::redirect::record_final_url;
+use crate::search_engine::scrape::{content_type, record_content_type};
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use parking_lot::MutexGuard;
 use rusqlite::{params, Connection};
+use scraper::{Html, Node};
 use std::io::Cursor;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -13,6 +16,16 @@ use zstd::{decode_all, encode_all};
 
 static HTML_CACHE: Lazy<Cache> = Lazy::new(Cache::new);
 static VERSION: u16 = 0;
+// Text in these elements is never shown on the page, so is not searched.
+static HIDDEN_ELEMENTS: [&str; 4] = ["script", "style", "noscript", "template"];
+
+/// A cached page matching a local search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedPage {
+    pub(crate) url: String,
+    pub(crate) title: String,
+    pub(crate) snippet: String,
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct CacheConfig {
@@ -47,16 +60,40 @@ impl Cache {
         cache
     }
 
+    // The html is compressed, so the text of each page is kept in a separate full text search
+    // table. This is only brought up to date when searching, so caching pages stays fast.
     fn init_db(&self) -> Result<(), IsError> {
-        self.get_connection().execute(
+        self.get_connection().execute_batch(
             "CREATE TABLE IF NOT EXISTS cache (
                 url TEXT PRIMARY KEY,
                 html BLOB NOT NULL,
                 timestamp INTEGER NOT NULL,
-                version INTEGER NOT NULL
-            )",
-            [],
+                version INTEGER NOT NULL,
+                content_type TEXT
+            );
+            CREATE TABLE IF NOT EXISTS cache_alias (
+                url TEXT PRIMARY KEY,
+                final_url TEXT NOT NULL
+            );
+            CREATE VIRTUAL TABLE IF NOT EXISTS cache_search USING fts5(
+                url UNINDEXED,
+                timestamp UNINDEXED,
+                title,
+                content
+            );",
         )?;
+        self.add_content_type_column()
+    }
+
+    // Databases created before the media type was stored don't have the column.
+    fn add_content_type_column(&self) -> Result<(), IsError> {
+        let connection = self.get_connection();
+        let has_column = connection
+            .prepare("SELECT 1 FROM pragma_table_info('cache') WHERE name = 'content_type'")?
+            .exists([])?;
+        if !has_column {
+            connection.execute("ALTER TABLE cache ADD COLUMN content_type TEXT", [])?;
+        }
         Ok(())
     }
 
@@ -73,11 +110,25 @@ impl Cache {
             self.purge_cache(cache_config)?;
         }
         let compressed_html = encode_all(Cursor::new(value), 3)?;
-        self.get_connection().execute(
-            "INSERT INTO cache (url, html, timestamp, version) VALUES (?, ?, ?, ?)
-             ON CONFLICT(url) DO UPDATE SET html = excluded.html, timestamp = excluded.timestamp",
-            params![key.get_url(), compressed_html, timestamp, VERSION],
+        // A redirected page is cached under the url it was redirected to, with the requested url
+        // as an alias, so it is found from either url.
+        let url = key.get_url();
+        let final_url = key.get_final_url();
+        // The media type is kept so the page is shown the same way when it is read from the cache.
+        let content_type = content_type(&final_url).or_else(|| content_type(url));
+        let connection = self.get_connection();
+        connection.execute(
+            "INSERT INTO cache (url, html, timestamp, version, content_type) VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(url) DO UPDATE SET html = excluded.html, timestamp = excluded.timestamp,
+             content_type = excluded.content_type",
+            params![final_url, compressed_html, timestamp, VERSION, content_type],
         )?;
+        if url != final_url {
+            connection.execute(
+                "INSERT OR REPLACE INTO cache_alias (url, final_url) VALUES (?, ?)",
+                params![url, final_url],
+            )?;
+        }
         Ok(())
     }
 
@@ -100,40 +151,65 @@ impl Cache {
             )",
             )?
             .execute(params![elements_to_retain])?;
+        self.get_connection().execute(
+            "DELETE FROM cache_alias WHERE final_url NOT IN (SELECT url FROM cache)",
+            [],
+        )?;
         Ok(())
     }
 
     pub fn get(&self, key: &HtmlSource) -> Result<Option<String>, IsError> {
         match key.get_config().get_cache().cache_mode {
-            CacheMode::Write | CacheMode::Never => return Ok(None),
+            CacheMode::Write | CacheMode::Never if !key.is_cached() => return Ok(None),
             _ => {}
         }
 
-        let values = self.retrieve_value(key.get_url())?;
+        let url = self.resolve_alias(key.get_url())?;
+        let values = self.retrieve_value(&url)?;
 
-        if let Some((html, timestamp)) = values {
+        if let Some((html, timestamp, content_type)) = values {
             if timestamp <= Self::current_time()? {
                 log::debug!("Expired cache item {}", key.get_url());
                 self.remove(key)?;
                 return Ok(None);
             }
+            record_final_url(key.get_url(), &url);
+            if let Some(content_type) = content_type {
+                record_content_type(key.get_url(), &content_type);
+                record_content_type(&url, &content_type);
+            }
             return Ok(Some(html));
         }
 
         Ok(None)
     }
 
-    fn retrieve_value(&self, key: &str) -> Result<Option<(String, i64)>, IsError> {
+    fn resolve_alias(&self, url: &str) -> Result<String, IsError> {
+        let connection = self.get_connection();
+        let mut stmt = connection.prepare("SELECT final_url FROM cache_alias WHERE url = ?")?;
+        let mut rows = stmt.query(params![url])?;
+        match rows.next()? {
+            Some(row) => Ok(row.get(0)?),
+            None => Ok(url.to_string()),
+        }
+    }
+
+    fn retrieve_value(&self, key: &str) -> Result<Option<(String, i64, Option<String>)>, IsError> {
         let connection = self.get_connection();
-        let mut stmt = connection.prepare("SELECT html, timestamp FROM cache WHERE url = ?")?;
+        let mut stmt =
+            connection.prepare("SELECT html, timestamp, content_type FROM cache WHERE url = ?")?;
         let mut rows = stmt.query(params![key])?;
         match rows.next()? {
             None => Ok(None),
             Some(row) => {
                 let compressed_html: Vec<u8> = row.get(0)?;
                 let timestamp: i64 = row.get(1)?;
-                let html = String::from_utf8(decode_all(Cursor::new(compressed_html))?)?;
-                Ok(Some((html, timestamp)))
+                let content_type: Option<String> = row.get(2)?;
+                Ok(Some((
+                    Self::decompress(compressed_html)?,
+                    timestamp,
+                    content_type,
+                )))
             }
         }
     }
@@ -142,19 +218,111 @@ impl Cache {
         if let CacheMode::Never | CacheMode::Read = key.get_config().get_cache().cache_mode {
             return Ok(());
         }
-        let connection = self.get_connection();
         let url = key.get_url();
-        connection.execute("DELETE FROM cache WHERE url = ?", params![url])?;
+        let final_url = self.resolve_alias(url)?;
+        let connection = self.get_connection();
+        connection.execute(
+            "DELETE FROM cache WHERE url IN (?1, ?2)",
+            params![url, final_url],
+        )?;
+        connection.execute("DELETE FROM cache_alias WHERE url = ?", params![url])?;
         drop(connection);
         log::debug!("Removing: {url} from cache");
         Ok(())
     }
 
     pub fn clear(&self) -> Result<(), IsError> {
-        self.get_connection().execute("DROP TABLE cache", [])?;
+        self.get_connection()
+            .execute_batch(
+                "DROP TABLE cache; DROP TABLE IF EXISTS cache_alias; DROP TABLE IF EXISTS cache_search;",
+            )?;
+        Ok(())
+    }
+
+    pub fn search(
+        &self,
+        query: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<CachedPage>, IsError> {
+        self.update_search_index()?;
+        let connection = self.get_connection();
+        let mut stmt = connection.prepare(
+            "SELECT url, title, snippet(cache_search, 3, '', '', '...', 24) FROM cache_search
+             WHERE cache_search MATCH ? AND timestamp > ?
+             ORDER BY rank LIMIT ? OFFSET ?",
+        )?;
+        let pages = stmt
+            .query_map(
+                params![query, Self::current_time()?, limit, offset],
+                |row| {
+                    Ok(CachedPage {
+                        url: row.get(0)?,
+                        title: row.get(1)?,
+                        snippet: row.get(2)?,
+                    })
+                },
+            )?
+            .collect::<Result<Vec<CachedPage>, _>>()?;
+        Ok(pages)
+    }
+
+    // Pages that have been removed or replaced since they were indexed are dropped, then any
+    // pages that are not yet indexed are added.
+    fn update_search_index(&self) -> Result<(), IsError> {
+        let connection = self.get_connection();
+        connection.execute(
+            "DELETE FROM cache_search WHERE NOT EXISTS (
+                SELECT 1 FROM cache
+                WHERE cache.url = cache_search.url AND cache.timestamp = cache_search.timestamp
+            )",
+            [],
+        )?;
+        let mut stmt = connection.prepare(
+            "SELECT url, html, timestamp FROM cache
+             WHERE url NOT IN (SELECT url FROM cache_search)",
+        )?;
+        let unindexed = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Vec<u8>>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })?
+            .collect::<Vec<_>>();
+        // A page that can't be read is left out of the index, so the other pages can still be
+        // found.
+        for row in unindexed {
+            let (url, compressed_html, timestamp) = match row {
+                Ok(row) => row,
+                Err(e) => {
+                    log::warn!("Skipping a cached page that could not be read: {e:?}");
+                    continue;
+                }
+            };
+            let html = match Self::decompress(compressed_html) {
+                Ok(html) => html,
+                Err(e) => {
+                    log::warn!("Skipping the cached page {url} that could not be read: {e:?}");
+                    continue;
+                }
+            };
+            let (title, content) = searchable_text(&html);
+            connection.execute(
+                "INSERT INTO cache_search (url, timestamp, title, content) VALUES (?, ?, ?, ?)",
+                params![url, timestamp, title, content],
+            )?;
+        }
         Ok(())
     }
 
+    fn decompress(compressed_html: Vec<u8>) -> Result<String, IsError> {
+        Ok(String::from_utf8(decode_all(Cursor::new(
+            compressed_html,
+        ))?)?)
+    }
+
     fn current_time() -> Result<i64, IsError> {
         Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as i64)
     }
@@ -192,6 +360,38 @@ pub fn cached_pages_purge(url: &HtmlSource) {
     });
 }
 
+pub fn cached_pages_search(query: &str, limit: usize, offset: usize) -> Vec<CachedPage> {
+    HTML_CACHE.search(query, limit, offset).unwrap_or_else(|e| {
+        log::error!("Error when searching the cache: {e:?}");
+        vec![]
+    })
+}
+
+fn searchable_text(html: &str) -> (String, String) {
+    let document = Html::parse_document(html);
+    let mut title = String::new();
+    let mut content = Vec::new();
+    for node in document.tree.root().descendants() {
+        let Node::Text(text) = node.value() else {
+            continue;
+        };
+        let parent = node
+            .parent()
+            .and_then(|parent| parent.value().as_element().map(|element| element.name()));
+        match parent {
+            Some("title") => title.push_str(text),
+            Some(name) if HIDDEN_ELEMENTS.contains(&name) => {}
+            _ => content.push(text.trim()),
+        }
+    }
+    let content = content
+        .into_iter()
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ");
+    (title.trim().to_string(), content)
+}
+
 pub fn clear() {
     HTML_CACHE
         .clear()
@@ -257,6 +457,83 @@ mod tests {
         );
     }
 
+    #[test]
+    #[serial]
+    fn test_redirected_page_is_cached_with_alias() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        env::set_var("XDG_DATA_HOME", temp_dir.path());
+        let cache = Cache::new();
+        TEST_CONFIG.write().cache =
+            CacheConfig::new(CacheMode::ReadWrite, 10, MS_IN_SECOND * 5, 50);
+        let moved = LinkSource(Link::new("http://old.example.com/cached-alias"));
+        let real = LinkSource(Link::new("https://example.com/cached-alias"));
+        record_final_url(moved.get_url(), real.get_url());
+
+        cache.insert(&moved, "<p>Real page</p>").unwrap();
+
+        assert_eq!(
+            cache.get(&real).unwrap(),
+            Some(String::from("<p>Real page</p>"))
+        );
+        assert_eq!(
+            cache.get(&moved).unwrap(),
+            Some(String::from("<p>Real page</p>"))
+        );
+        cache.remove(&moved).unwrap();
+        assert_eq!(cache.get(&real).unwrap(), None);
+    }
+
+    #[test]
+    #[serial]
+    fn test_cached_page_keeps_content_type() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        env::set_var("XDG_DATA_HOME", temp_dir.path());
+        let cache = Cache::new();
+        TEST_CONFIG.write().cache =
+            CacheConfig::new(CacheMode::ReadWrite, 10, MS_IN_SECOND * 5, 50);
+        let short = LinkSource(Link::new("http://short.example.com/notes"));
+        let notes = LinkSource(Link::new("https://example.com/notes"));
+        record_final_url(short.get_url(), notes.get_url());
+        record_content_type(notes.get_url(), "text/plain");
+
+        cache.insert(&short, "Plain <notes>").unwrap();
+
+        assert_eq!(content_type(short.get_url()), None);
+        assert_eq!(
+            cache.get(&short).unwrap(),
+            Some(String::from("Plain <notes>"))
+        );
+        assert_eq!(
+            content_type(short.get_url()),
+            Some(String::from("text/plain"))
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_content_type_column_is_added_to_existing_cache() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        env::set_var("XDG_DATA_HOME", temp_dir.path());
+        Connection::open(database_path())
+            .expect("Failed to open cache")
+            .execute_batch(
+                "CREATE TABLE cache (
+                    url TEXT PRIMARY KEY,
+                    html BLOB NOT NULL,
+                    timestamp INTEGER NOT NULL,
+                    version INTEGER NOT NULL
+                );",
+            )
+            .unwrap();
+
+        let cache = Cache::new();
+
+        assert!(cache
+            .get_connection()
+            .prepare("SELECT content_type FROM cache")
+            .is_ok());
+    }
+
     #[test]
     #[serial]
     fn test_cache_ttl_removes_expired() {
@@ -300,4 +577,101 @@ mod tests {
             .unwrap()
             .is_none());
     }
+
+    #[test]
+    #[serial]
+    fn test_cache_search_finds_unexpired_pages() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path();
+        env::set_var("XDG_DATA_HOME", path);
+        let cache = Cache::new();
+        TEST_CONFIG.write().cache =
+            CacheConfig::new(CacheMode::ReadWrite, 10, MS_IN_SECOND * 5, 50);
+        cache
+            .insert(
+                &LinkSource(Link::new("https://www.baeldung.com/spring-boot")),
+                "<html><head><title>Spring Boot</title><script>var rust;</script></head>\
+                 <body><p>Learn Spring Boot with Baeldung</p></body></html>",
+            )
+            .unwrap();
+        cache
+            .insert(
+                &LinkSource(Link::new("https://www.rust-lang.org")),
+                "<html><head><title>Rust</title></head><body>A language</body></html>",
+            )
+            .unwrap();
+
+        let pages = cache.search("\"baeldung\"", 10, 0).unwrap();
+
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].url, "https://www.baeldung.com/spring-boot");
+        assert_eq!(pages[0].title, "Spring Boot");
+        assert!(pages[0].snippet.contains("Baeldung"));
+        assert_eq!(cache.search("\"rust\"", 10, 0).unwrap().len(), 1);
+
+        TEST_CONFIG.write().cache = CacheConfig::new(CacheMode::ReadWrite, 10, 0, 50);
+        cache
+            .insert(
+                &LinkSource(Link::new("https://www.baeldung.com/spring-boot")),
+                "<html><body>Baeldung</body></html>",
+            )
+            .unwrap();
+        assert!(cache.search("\"baeldung\"", 10, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn test_cache_search_skips_unreadable_pages() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        env::set_var("XDG_DATA_HOME", temp_dir.path());
+        let cache = Cache::new();
+        TEST_CONFIG.write().cache =
+            CacheConfig::new(CacheMode::ReadWrite, 10, MS_IN_SECOND * 5, 50);
+        cache
+            .insert(
+                &LinkSource(Link::new("https://www.rust-lang.org")),
+                "<html><head><title>Rust</title></head><body>A language</body></html>",
+            )
+            .unwrap();
+        let expiry = Cache::current_time().unwrap() + MS_IN_SECOND * 5;
+        cache
+            .get_connection()
+            .execute(
+                "INSERT INTO cache (url, html, timestamp, version) VALUES (?, ?, ?, ?)",
+                params![
+                    "https://broken.example.com",
+                    b"not zstd".to_vec(),
+                    expiry,
+                    VERSION
+                ],
+            )
+            .unwrap();
+
+        let pages = cache.search("\"language\"", 10, 0).unwrap();
+
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].url, "https://www.rust-lang.org");
+    }
+
+    #[test]
+    #[serial]
+    fn test_cached_link_ignores_cache_mode() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path();
+        env::set_var("XDG_DATA_HOME", path);
+        let cache = Cache::new();
+        TEST_CONFIG.write().cache =
+            CacheConfig::new(CacheMode::ReadWrite, 10, MS_IN_SECOND * 5, 50);
+        let url = "https://www.rust-lang.org";
+        cache.insert(&LinkSource(Link::new(url)), "html").unwrap();
+        TEST_CONFIG.write().cache = CacheConfig::new(CacheMode::Never, 10, MS_IN_SECOND * 5, 50);
+
+        assert!(cache.get(&LinkSource(Link::new(url))).unwrap().is_none());
+        assert_eq!(
+            cache
+                .get(&LinkSource(Link::from_cache(url, "", "", "local")))
+                .unwrap(),
+            Some(String::from("html"))
+        );
+    }
 }
@@ -0,0 +1,541 @@
+use crate::config::files::{config_location, database_path};
+use crate::errors::error::IsError;
+use crate::errors::error::IsError::Scrape;
+use chrono::{DateTime, NaiveDateTime};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use ureq::http::Uri;
+
+static COOKIE_JAR: Lazy<CookieJar> = Lazy::new(|| {
+    CookieJar::new(Connection::open(database_path()).expect("Failed to open database"))
+        .expect("Failed to initialize database")
+});
+static IMPORTED_COOKIES: Lazy<DashMap<PathBuf, Vec<Cookie>>> = Lazy::new(DashMap::new);
+// Second level labels that country code domains are registered under, such as co.uk or com.au.
+const SECOND_LEVEL_LABELS: [&str; 12] = [
+    "ac", "co", "com", "edu", "go", "gov", "ltd", "ne", "net", "or", "org", "plc",
+];
+
+/// Whether cookies set by a site are remembered, and a cookies.txt file to import cookies for the
+/// site from. The path is relative to the config directory unless it is absolute.
+#[derive(Debug, Clone, Default)]
+pub struct CookieSettings {
+    store: bool,
+    import: Option<PathBuf>,
+}
+
+impl CookieSettings {
+    pub fn new(store: bool, import: Option<String>) -> Self {
+        Self {
+            store,
+            import: import.map(|path| config_location().join(path)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Cookie {
+    domain: String,
+    // Host only cookies are not sent to subdomains.
+    host_only: bool,
+    path: String,
+    name: String,
+    value: String,
+    secure: bool,
+    // Unix seconds, with None for a session cookie.
+    expires: Option<i64>,
+}
+
+impl Cookie {
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    fn matches(&self, target: &Target, now: i64) -> bool {
+        let domain_matches = if self.host_only {
+            target.host == self.domain
+        } else {
+            domain_match(&target.host, &self.domain)
+        };
+        domain_matches
+            && path_match(&target.path, &self.path)
+            && (!self.secure || target.secure)
+            && !self.is_expired(now)
+    }
+}
+
+struct Target {
+    host: String,
+    path: String,
+    secure: bool,
+}
+
+impl Target {
+    fn parse(url: &str) -> Option<Self> {
+        let uri = url.parse::<Uri>().ok()?;
+        Some(Self {
+            host: uri.host()?.to_lowercase(),
+            path: uri.path().to_string(),
+            secure: uri.scheme_str() == Some("https"),
+        })
+    }
+}
+
+struct CookieJar {
+    connection: Mutex<Connection>,
+}
+
+impl CookieJar {
+    fn new(connection: Connection) -> Result<Self, IsError> {
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS cookies (
+                domain TEXT NOT NULL,
+                host_only INTEGER NOT NULL,
+                path TEXT NOT NULL,
+                name TEXT NOT NULL,
+                value TEXT NOT NULL,
+                secure INTEGER NOT NULL,
+                expires INTEGER,
+                PRIMARY KEY (domain, path, name)
+            );",
+        )?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn store(&self, cookie: &Cookie, now: i64) -> Result<(), IsError> {
+        let conn = self.connection.lock();
+        // An expired cookie is how a site removes it.
+        if cookie.is_expired(now) {
+            conn.execute(
+                "DELETE FROM cookies WHERE domain = ?1 AND path = ?2 AND name = ?3",
+                params![cookie.domain, cookie.path, cookie.name],
+            )?;
+        } else {
+            conn.execute(
+                "INSERT OR REPLACE INTO cookies (domain, host_only, path, name, value, secure, expires)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    cookie.domain,
+                    cookie.host_only,
+                    cookie.path,
+                    cookie.name,
+                    cookie.value,
+                    cookie.secure,
+                    cookie.expires
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn cookies_for(&self, target: &Target, now: i64) -> Result<Vec<Cookie>, IsError> {
+        let conn = self.connection.lock();
+        conn.execute("DELETE FROM cookies WHERE expires <= ?1", [now])?;
+        let mut stmt = conn.prepare(
+            "SELECT domain, host_only, path, name, value, secure, expires FROM cookies
+             WHERE domain = ?1 OR ?1 LIKE '%.' || domain",
+        )?;
+        let cookies = stmt
+            .query_map([&target.host], |row| {
+                Ok(Cookie {
+                    domain: row.get(0)?,
+                    host_only: row.get(1)?,
+                    path: row.get(2)?,
+                    name: row.get(3)?,
+                    value: row.get(4)?,
+                    secure: row.get(5)?,
+                    expires: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<Cookie>, _>>()?;
+        Ok(cookies
+            .into_iter()
+            .filter(|cookie| cookie.matches(target, now))
+            .collect())
+    }
+}
+
+/// Creates the Cookie header value for the url from the imported and stored cookies, or None when
+/// there are no cookies to send.
+pub fn cookie_header(url: &str, settings: &CookieSettings) -> Result<Option<String>, IsError> {
+    if !settings.store && settings.import.is_none() {
+        return Ok(None);
+    }
+    let Some(target) = Target::parse(url) else {
+        return Ok(None);
+    };
+    let now = now();
+    let mut cookies = Vec::new();
+    if settings.store {
+        cookies.extend(COOKIE_JAR.cookies_for(&target, now)?);
+    }
+    if let Some(import) = &settings.import {
+        cookies.extend(
+            imported_cookies(import)?
+                .into_iter()
+                .filter(|cookie| cookie.matches(&target, now)),
+        );
+    }
+    Ok(format_cookie_header(cookies))
+}
+
+/// Remembers the cookies set by the response, if the site stores cookies.
+pub fn store_cookies<'a>(
+    url: &str,
+    settings: &CookieSettings,
+    set_cookies: impl Iterator<Item = &'a str>,
+) {
+    if !settings.store {
+        return;
+    }
+    let Some(target) = Target::parse(url) else {
+        return;
+    };
+    let now = now();
+    for set_cookie in set_cookies {
+        match parse_set_cookie(&target, set_cookie, now) {
+            Some(cookie) => {
+                if let Err(e) = COOKIE_JAR.store(&cookie, now) {
+                    log::error!("Failed to store cookie {} for {url}: {e}", cookie.name);
+                }
+            }
+            None => log::debug!("Ignoring cookie for {url}: {set_cookie}"),
+        }
+    }
+}
+
+fn imported_cookies(path: &Path) -> Result<Vec<Cookie>, IsError> {
+    if let Some(cookies) = IMPORTED_COOKIES.get(path) {
+        return Ok(cookies.clone());
+    }
+    let text = fs::read_to_string(path)
+        .map_err(|e| Scrape(format!("Could not read {}: {e}", path.display())))?;
+    let cookies = parse_cookies_file(&text);
+    log::debug!("Imported {} cookies from {}", cookies.len(), path.display());
+    IMPORTED_COOKIES.insert(path.to_path_buf(), cookies.clone());
+    Ok(cookies)
+}
+
+// Cookies with the same name are sent once, with the stored cookie taking priority as it was set
+// by the site more recently. More specific paths are sent first.
+fn format_cookie_header(mut cookies: Vec<Cookie>) -> Option<String> {
+    let mut seen = HashSet::new();
+    cookies.retain(|cookie| seen.insert((cookie.name.clone(), cookie.path.clone())));
+    cookies.sort_by_key(|cookie| Reverse(cookie.path.len()));
+    Some(
+        cookies
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect::<Vec<String>>()
+            .join("; "),
+    )
+    .filter(|header| !header.is_empty())
+}
+
+fn parse_set_cookie(target: &Target, set_cookie: &str, now: i64) -> Option<Cookie> {
+    let mut parts = set_cookie.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let mut cookie = Cookie {
+        domain: target.host.clone(),
+        host_only: true,
+        path: default_path(&target.path),
+        name: name.to_string(),
+        value: value.trim().trim_matches('"').to_string(),
+        secure: false,
+        expires: None,
+    };
+    let mut max_age = None;
+    for attribute in parts {
+        let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+        let value = value.trim();
+        match key.trim().to_lowercase().as_str() {
+            "domain" if !value.is_empty() => {
+                let domain = value.trim_start_matches('.').to_lowercase();
+                // A site can only set cookies for itself and its parent domains.
+                if !domain_match(&target.host, &domain) {
+                    return None;
+                }
+                // A cookie for a public suffix would be sent to every site under it, so it is
+                // only kept when the suffix is the site itself.
+                if is_public_suffix(&domain) {
+                    if domain != target.host {
+                        log::debug!("Ignoring the cookie {name} set for {domain}");
+                        return None;
+                    }
+                    continue;
+                }
+                cookie.domain = domain;
+                cookie.host_only = false;
+            }
+            "path" if value.starts_with('/') => cookie.path = value.to_string(),
+            "secure" => cookie.secure = true,
+            "max-age" => max_age = value.parse::<i64>().ok(),
+            "expires" => cookie.expires = cookie.expires.or_else(|| parse_expires(value)),
+            _ => {}
+        }
+    }
+    // Max-Age takes priority over Expires.
+    if let Some(max_age) = max_age {
+        cookie.expires = Some(now.saturating_add(max_age));
+    }
+    Some(cookie)
+}
+
+// The Netscape format used by browser extensions and curl, with one tab separated cookie per line:
+// domain, include subdomains, path, secure, expires, name and value.
+fn parse_cookies_file(text: &str) -> Vec<Cookie> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+            if line.trim().is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let fields = line.split('\t').collect::<Vec<&str>>();
+            let [domain, subdomains, path, secure, expires, name, value] = fields[..] else {
+                log::warn!("Skipping invalid line in cookies file: {line}");
+                return None;
+            };
+            Some(Cookie {
+                domain: domain.trim_start_matches('.').to_lowercase(),
+                host_only: !subdomains.eq_ignore_ascii_case("TRUE"),
+                path: path.to_string(),
+                name: name.to_string(),
+                value: value.trim_end_matches('\r').to_string(),
+                secure: secure.eq_ignore_ascii_case("TRUE"),
+                expires: expires.parse::<i64>().ok().filter(|expires| *expires > 0),
+            })
+        })
+        .collect()
+}
+
+fn parse_expires(value: &str) -> Option<i64> {
+    DateTime::parse_from_rfc2822(value)
+        .map(|date| date.timestamp())
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, "%a, %d-%b-%Y %H:%M:%S GMT")
+                .map(|date| date.and_utc().timestamp())
+        })
+        .ok()
+}
+
+// Without the public suffix list, these are found as top level domains, such as com, and the
+// second level domains of country codes, such as co.uk.
+fn is_public_suffix(domain: &str) -> bool {
+    match domain.split('.').collect::<Vec<&str>>()[..] {
+        [_] => true,
+        [second, top] => top.len() == 2 && SECOND_LEVEL_LABELS.contains(&second),
+        _ => false,
+    }
+}
+
+fn domain_match(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{domain}"))
+}
+
+fn path_match(request_path: &str, cookie_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
+}
+
+// Without a Path attribute the cookie is for the directory of the page that set it.
+fn default_path(request_path: &str) -> String {
+    match request_path.rfind('/') {
+        Some(0) | None => String::from("/"),
+        Some(end) => request_path[..end].to_string(),
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn target(url: &str) -> Target {
+        Target::parse(url).unwrap()
+    }
+
+    fn jar() -> CookieJar {
+        CookieJar::new(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_set_cookie_attributes() {
+        let cookie = parse_set_cookie(
+            &target("https://www.example.com/questions/1"),
+            "consent=yes; Domain=.example.com; Path=/; Max-Age=60; Secure; HttpOnly",
+            NOW,
+        )
+        .unwrap();
+
+        assert_eq!(
+            cookie,
+            Cookie {
+                domain: String::from("example.com"),
+                host_only: false,
+                path: String::from("/"),
+                name: String::from("consent"),
+                value: String::from("yes"),
+                secure: true,
+                expires: Some(NOW + 60),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_set_cookie_defaults_to_host_and_directory() {
+        let cookie = parse_set_cookie(
+            &target("https://example.com/docs/page"),
+            "id=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT",
+            NOW,
+        )
+        .unwrap();
+
+        assert!(cookie.host_only);
+        assert_eq!(cookie.domain, "example.com");
+        assert_eq!(cookie.path, "/docs");
+        assert_eq!(cookie.expires, Some(1_445_412_480));
+    }
+
+    #[test]
+    fn test_cookie_for_other_domain_is_rejected() {
+        assert_eq!(
+            parse_set_cookie(
+                &target("https://example.com"),
+                "id=1; Domain=tracker.com",
+                NOW
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cookie_for_public_suffix_is_rejected() {
+        for (url, set_cookie) in [
+            ("https://example.com", "id=1; Domain=com"),
+            ("https://foo.co.uk", "id=1; Domain=.co.uk"),
+            ("https://shop.example.com.au", "id=1; Domain=com.au"),
+        ] {
+            assert_eq!(parse_set_cookie(&target(url), set_cookie, NOW), None);
+        }
+        let cookie =
+            parse_set_cookie(&target("https://localhost/"), "id=1; Domain=localhost", NOW).unwrap();
+        assert!(cookie.host_only);
+        assert!(
+            parse_set_cookie(&target("https://foo.co.uk"), "id=1; Domain=foo.co.uk", NOW)
+                .is_some_and(|cookie| !cookie.host_only)
+        );
+    }
+
+    #[test]
+    fn test_parse_cookies_file() {
+        let file = "# Netscape HTTP Cookie File\n\
+            .stackoverflow.com\tTRUE\t/\tTRUE\t0\tOptanonAlertBoxClosed\t2025-01-01\n\
+            #HttpOnly_finance.yahoo.com\tFALSE\t/quote\tFALSE\t1900000000\tGUCS\tabc\n\
+            invalid line\n";
+
+        let cookies = parse_cookies_file(file);
+
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[0].domain, "stackoverflow.com");
+        assert!(!cookies[0].host_only && cookies[0].secure);
+        assert_eq!(cookies[0].expires, None);
+        assert_eq!(cookies[1].domain, "finance.yahoo.com");
+        assert!(cookies[1].host_only);
+        assert_eq!(cookies[1].expires, Some(1_900_000_000));
+    }
+
+    #[test]
+    fn test_jar_returns_matching_cookies() {
+        let jar = jar();
+        let site = target("https://www.example.com/a/b");
+        for set_cookie in [
+            "shared=1; Domain=example.com; Path=/",
+            "host=2",
+            "secure=3; Secure",
+            "other=4; Path=/c",
+        ] {
+            jar.store(&parse_set_cookie(&site, set_cookie, NOW).unwrap(), NOW)
+                .unwrap();
+        }
+
+        let names = |url: &str| {
+            let mut names = jar
+                .cookies_for(&target(url), NOW)
+                .unwrap()
+                .into_iter()
+                .map(|cookie| cookie.name)
+                .collect::<Vec<String>>();
+            names.sort();
+            names
+        };
+        assert_eq!(
+            names("https://www.example.com/a/b"),
+            ["host", "secure", "shared"]
+        );
+        assert_eq!(names("http://www.example.com/a/c"), ["host", "shared"]);
+        assert_eq!(names("https://docs.example.com/"), ["shared"]);
+        assert!(names("https://example.org/a").is_empty());
+    }
+
+    #[test]
+    fn test_expired_cookie_removes_stored_cookie() {
+        let jar = jar();
+        let site = target("https://example.com/");
+        jar.store(&parse_set_cookie(&site, "id=1", NOW).unwrap(), NOW)
+            .unwrap();
+        jar.store(
+            &parse_set_cookie(&site, "id=; Max-Age=0", NOW).unwrap(),
+            NOW,
+        )
+        .unwrap();
+
+        assert!(jar.cookies_for(&site, NOW).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cookie_header_prefers_first_and_longest_path() {
+        let site = target("https://example.com/a/page");
+        let cookies = [
+            "id=stored; Path=/",
+            "lang=en; Path=/a",
+            "id=imported; Path=/",
+        ]
+        .iter()
+        .map(|set_cookie| parse_set_cookie(&site, set_cookie, NOW).unwrap())
+        .collect();
+
+        assert_eq!(
+            format_cookie_header(cookies),
+            Some(String::from("lang=en; id=stored"))
+        );
+        assert_eq!(format_cookie_header(vec![]), None);
+    }
+
+    #[test]
+    fn test_path_match() {
+        assert!(path_match("/docs/page", "/docs"));
+        assert!(path_match("/docs/page", "/"));
+        assert!(!path_match("/documents", "/docs"));
+    }
+}
@@ -0,0 +1,155 @@
+use crate::search_engine::link::Link;
+
+/// Restricts the search results to certain domains. Sites passed with `--site` or set in the
+/// config are added to the query for the engines that understand `site:`, but every result is
+/// also checked after the search so the restriction holds for any engine.
+#[derive(Debug, Clone)]
+pub struct DomainFilter {
+    allow: Vec<String>,
+    deny: Vec<String>,
+    query: String,
+}
+
+impl DomainFilter {
+    /// Sites starting with `-` are excluded, the rest are OR'd together. When any sites are
+    /// included they replace the allow list.
+    pub fn new(sites: &[String], allow_domains: &[String], deny_domains: &[String]) -> Self {
+        let (excluded, included): (Vec<&str>, Vec<&str>) = sites
+            .iter()
+            .map(|site| site.trim())
+            .partition(|site| site.starts_with('-'));
+        let (excluded, included) = (normalize_sites(&excluded), normalize_sites(&included));
+        let query = included
+            .iter()
+            .map(|site| format!("site:{site}"))
+            .collect::<Vec<String>>()
+            .join("+OR+");
+        let exclusions = excluded.iter().map(|site| format!("-site:{site}"));
+        let query = std::iter::once(query)
+            .filter(|query| !query.is_empty())
+            .chain(exclusions)
+            .collect::<Vec<String>>()
+            .join("+");
+        let allow = if included.is_empty() {
+            normalize_sites(allow_domains)
+        } else {
+            included
+        };
+        let mut deny = excluded;
+        deny.extend(normalize_sites(deny_domains));
+        Self { allow, deny, query }
+    }
+
+    /// The site restrictions to add to the search query.
+    pub fn query_terms(&self) -> &str {
+        &self.query
+    }
+
+    pub fn filter(&self, links: Vec<Link>) -> Vec<Link> {
+        links
+            .into_iter()
+            .filter(|link| {
+                let allowed = self.allows(&link.url);
+                if !allowed {
+                    log::debug!("Filtered out search result {}", link.url);
+                }
+                allowed
+            })
+            .collect()
+    }
+
+    fn allows(&self, url: &str) -> bool {
+        let host = host(url);
+        (self.allow.is_empty() || self.allow.iter().any(|domain| matches(&host, domain)))
+            && !self.deny.iter().any(|domain| matches(&host, domain))
+    }
+}
+
+fn normalize_sites<S: AsRef<str>>(sites: &[S]) -> Vec<String> {
+    sites
+        .iter()
+        .map(|site| site.as_ref().trim_start_matches('-'))
+        .map(|site| site.strip_prefix("site:").unwrap_or(site))
+        .map(host)
+        .filter(|site| !site.is_empty())
+        .collect()
+}
+
+fn host(url: &str) -> String {
+    let url = url.trim().to_lowercase();
+    let url = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(&url);
+    let host = url.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.split(':').next().unwrap_or_default();
+    host.strip_prefix("www.").unwrap_or(host).to_string()
+}
+
+// Subdomains are included, so en.wikipedia.org matches wikipedia.org.
+fn matches(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{domain}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_query_terms_or_sites_and_exclude() {
+        let filter = DomainFilter::new(
+            &strings(&["docs.rs", "site:www.rust-lang.org", "-site:pinterest.com"]),
+            &[],
+            &[],
+        );
+
+        assert_eq!(
+            filter.query_terms(),
+            "site:docs.rs+OR+site:rust-lang.org+-site:pinterest.com"
+        );
+    }
+
+    #[test]
+    fn test_query_terms_empty_without_sites() {
+        assert_eq!(DomainFilter::new(&[], &[], &[]).query_terms(), "");
+    }
+
+    #[test]
+    fn test_filter_applies_sites_and_deny_list() {
+        let filter = DomainFilter::new(
+            &strings(&["wikipedia.org", "-de.wikipedia.org"]),
+            &strings(&["ignored.com"]),
+            &strings(&["contentfarm.com"]),
+        );
+
+        assert!(filter.allows("https://en.wikipedia.org/wiki/Rust"));
+        assert!(!filter.allows("https://de.wikipedia.org/wiki/Rust"));
+        assert!(!filter.allows("https://ignored.com"));
+        assert!(!filter.allows("https://www.rust-lang.org"));
+    }
+
+    #[test]
+    fn test_filter_uses_allow_list_without_sites() {
+        let filter = DomainFilter::new(
+            &[],
+            &strings(&["rust-lang.org"]),
+            &strings(&["https://www.contentfarm.com/"]),
+        );
+        let links = vec![
+            Link::new("https://doc.rust-lang.org/book"),
+            Link::new("https://notrust-lang.org"),
+            Link::new("https://contentfarm.com/rust"),
+        ];
+
+        let filtered = filter.filter(links);
+
+        let urls = filtered.iter().map(|l| l.url.as_str()).collect::<Vec<_>>();
+        assert_eq!(urls, vec!["https://doc.rust-lang.org/book"]);
+        assert!(!DomainFilter::new(&[], &[], &strings(&["contentfarm.com"]))
+            .allows("http://www.contentfarm.com:8080/page"));
+    }
+}
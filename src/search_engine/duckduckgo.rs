@@ -1,34 +1,151 @@
+use crate::cli::command::SafeSearch;
+use crate::config::load::Config;
 use crate::errors::error::IsError;
 use crate::errors::error::IsError::{Search as SearchError, Selector as SelectorError};
 use crate::search_engine::link::HtmlSource::LinkSource;
 use crate::search_engine::link::Link;
+use crate::search_engine::locale::SearchLocale;
 use crate::search_engine::scrape::{cache_purge, scrape};
 use crate::search_engine::search_type::Search;
+use once_cell::sync::Lazy;
 use scraper::{Html, Selector};
 
+pub(crate) const ENGINE_NAME: &str = "duckduckgo";
+// The html results page shows this many results, the next page is found with the s= offset.
+const PAGE_SIZE: usize = 30;
+
+// The regions DuckDuckGo supports, with the languages it has for each. The first language listed
+// for a region is its default.
+const REGIONS: &[(&str, &str, &str)] = &[
+    ("ar", "es", "ar-es"),
+    ("at", "de", "at-de"),
+    ("au", "en", "au-en"),
+    ("be", "fr", "be-fr"),
+    ("be", "nl", "be-nl"),
+    ("bg", "bg", "bg-bg"),
+    ("br", "pt", "br-pt"),
+    ("ca", "en", "ca-en"),
+    ("ca", "fr", "ca-fr"),
+    ("ch", "de", "ch-de"),
+    ("ch", "fr", "ch-fr"),
+    ("ch", "it", "ch-it"),
+    ("cl", "es", "cl-es"),
+    ("cn", "zh", "cn-zh"),
+    ("co", "es", "co-es"),
+    ("cz", "cs", "cz-cs"),
+    ("de", "de", "de-de"),
+    ("dk", "da", "dk-da"),
+    ("ee", "et", "ee-et"),
+    ("es", "es", "es-es"),
+    ("es", "ca", "es-ca"),
+    ("fi", "fi", "fi-fi"),
+    ("fr", "fr", "fr-fr"),
+    ("gb", "en", "uk-en"),
+    ("gr", "el", "gr-el"),
+    ("hk", "zh", "hk-tzh"),
+    ("hr", "hr", "hr-hr"),
+    ("hu", "hu", "hu-hu"),
+    ("id", "id", "id-id"),
+    ("id", "en", "id-en"),
+    ("ie", "en", "ie-en"),
+    ("il", "he", "il-he"),
+    ("in", "en", "in-en"),
+    ("it", "it", "it-it"),
+    ("jp", "ja", "jp-jp"),
+    ("kr", "ko", "kr-kr"),
+    ("lt", "lt", "lt-lt"),
+    ("lv", "lv", "lv-lv"),
+    ("mx", "es", "mx-es"),
+    ("my", "ms", "my-ms"),
+    ("my", "en", "my-en"),
+    ("nl", "nl", "nl-nl"),
+    ("no", "no", "no-no"),
+    ("nz", "en", "nz-en"),
+    ("pe", "es", "pe-es"),
+    ("ph", "en", "ph-en"),
+    ("ph", "tl", "ph-tl"),
+    ("pl", "pl", "pl-pl"),
+    ("pt", "pt", "pt-pt"),
+    ("ro", "ro", "ro-ro"),
+    ("ru", "ru", "ru-ru"),
+    ("se", "sv", "se-sv"),
+    ("sg", "en", "sg-en"),
+    ("sk", "sk", "sk-sk"),
+    ("sl", "sl", "sl-sl"),
+    ("th", "th", "th-th"),
+    ("tr", "tr", "tr-tr"),
+    ("tw", "zh", "tw-tzh"),
+    ("ua", "uk", "ua-uk"),
+    ("us", "en", "us-en"),
+    ("us", "es", "us-es"),
+    ("vn", "vi", "vn-vi"),
+    ("za", "en", "za-en"),
+];
+
+static LOCALE: Lazy<&SearchLocale> = Lazy::new(Config::get_search_locale);
+
 #[derive(Debug, Clone)]
 pub struct DuckDuckGoSearch;
 impl DuckDuckGoSearch {
-    pub fn get_links(search_term: &str) -> Result<Vec<Link>, IsError> {
-        let html_source = &LinkSource(Link::new(&format!(
-            "https://html.duckduckgo.com/html/?q={}",
-            &search_term
-        )));
+    pub fn get_links(search_term: &str, page: usize) -> Result<Vec<Link>, IsError> {
+        let html_source = &LinkSource(Link::new(&Self::search_url(search_term, page, &LOCALE)));
         scrape(html_source)
             .and_then(|html| Self::links_from_html(&html).inspect_err(|_| cache_purge(html_source)))
     }
 
+    fn search_url(search_term: &str, page: usize, locale: &SearchLocale) -> String {
+        let mut url = format!("https://html.duckduckgo.com/html/?q={search_term}");
+        if page > 0 {
+            url.push_str(&format!("&s={}", page * PAGE_SIZE));
+        }
+        if let Some(region) = Self::region(locale) {
+            url.push_str(&format!("&kl={region}"));
+        }
+        if let Some(safe_search) = &locale.safe_search {
+            let kp = match safe_search {
+                SafeSearch::Off => "-2",
+                SafeSearch::Moderate => "-1",
+                SafeSearch::Strict => "1",
+            };
+            url.push_str(&format!("&kp={kp}"));
+        }
+        url
+    }
+
+    // DuckDuckGo combines the region and language into its own codes, e.g. uk-en or de-de. It has
+    // no language only setting, so the language is only used along with a region, and a language
+    // the region doesn't have is replaced with the main language of the region.
+    fn region(locale: &SearchLocale) -> Option<String> {
+        let region = locale.region.as_deref()?;
+        let mut languages = REGIONS.iter().filter(|(code, _, _)| *code == region);
+        let Some(default) = languages.clone().next() else {
+            log::warn!("DuckDuckGo does not support the region {region}, searching all regions");
+            return None;
+        };
+        let (_, _, code) = languages
+            .find(|(_, language, _)| Some(*language) == locale.language.as_deref())
+            .unwrap_or(default);
+        Some(code.to_string())
+    }
+
     fn links_from_html(html: &str) -> Result<Vec<Link>, IsError> {
         let selector = Selector::parse("div.web-result")
             .map_err(|_| SelectorError(String::from("Failed to create a result selector")))?;
         let selector_url = Selector::parse("a.result__url")
             .map_err(|_| SelectorError(String::from("Failed to create url selector")))?;
+        let selector_title = Selector::parse("a.result__a")
+            .map_err(|_| SelectorError(String::from("Failed to create title selector")))?;
+        let selector_snippet = Selector::parse(".result__snippet")
+            .map_err(|_| SelectorError(String::from("Failed to create snippet selector")))?;
         Html::parse_document(html)
             .select(&selector)
             .map(|element_ref| Html::parse_document(&element_ref.html()))
             .map(|element_html| {
                 let url = Self::extract_value(&selector_url, &element_html)?;
-                Ok(Link::new(&url))
+                let title = Self::extract_value(&selector_title, &element_html).unwrap_or_default();
+                let snippet =
+                    Self::extract_value(&selector_snippet, &element_html).unwrap_or_default();
+                Ok(Link::from_search(&url, &title, &snippet, ENGINE_NAME))
             })
             .collect::<Result<Vec<Link>, IsError>>()
             .and_then(|links| {
@@ -55,7 +172,86 @@ impl DuckDuckGoSearch {
 }
 
 impl Search for DuckDuckGoSearch {
-    fn search(&self, query: &str) -> Result<Vec<Link>, IsError> {
-        Self::get_links(query)
+    fn search(&self, query: &str, page: usize) -> Result<Vec<Link>, IsError> {
+        Self::get_links(query, page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_url_pages_with_offset() {
+        assert_eq!(
+            DuckDuckGoSearch::search_url("rust", 0, &SearchLocale::default()),
+            "https://html.duckduckgo.com/html/?q=rust"
+        );
+        assert_eq!(
+            DuckDuckGoSearch::search_url("rust", 2, &SearchLocale::default()),
+            "https://html.duckduckgo.com/html/?q=rust&s=60"
+        );
+    }
+
+    #[test]
+    fn test_search_url_with_locale() {
+        let locale = SearchLocale::new(Some(String::from("GB")), None, Some(SafeSearch::Strict));
+        assert_eq!(
+            DuckDuckGoSearch::search_url("rust", 0, &locale),
+            "https://html.duckduckgo.com/html/?q=rust&kl=uk-en&kp=1"
+        );
+        let locale = SearchLocale::new(Some(String::from("de")), Some(String::from("de")), None);
+        assert_eq!(
+            DuckDuckGoSearch::search_url("rust", 0, &locale),
+            "https://html.duckduckgo.com/html/?q=rust&kl=de-de"
+        );
+    }
+
+    #[test]
+    fn test_region_defaults_to_its_main_language() {
+        let region = |region: &str, language: Option<&str>| {
+            DuckDuckGoSearch::region(&SearchLocale::new(
+                Some(String::from(region)),
+                language.map(String::from),
+                None,
+            ))
+        };
+
+        assert_eq!(region("fr", None).as_deref(), Some("fr-fr"));
+        assert_eq!(region("us", None).as_deref(), Some("us-en"));
+        assert_eq!(region("ca", Some("fr")).as_deref(), Some("ca-fr"));
+        assert_eq!(region("de", Some("en")).as_deref(), Some("de-de"));
+        assert_eq!(region("jp", Some("ja")).as_deref(), Some("jp-jp"));
+        assert_eq!(region("zz", None), None);
+    }
+
+    #[test]
+    fn test_links_from_html_keeps_title_and_snippet() {
+        let html = r#"<html><body>
+            <div class="result results_links web-result">
+                <h2 class="result__title"><a class="result__a" href="/l/?uddg=1">The Rust <b>Programming</b> Language</a></h2>
+                <a class="result__url" href="/l/?uddg=1"> doc.rust-lang.org/book/ </a>
+                <a class="result__snippet" href="/l/?uddg=1">An introductory book about Rust.</a>
+            </div>
+            <div class="result results_links web-result">
+                <a class="result__url" href="/l/?uddg=2">www.rust-lang.org</a>
+            </div>
+        </body></html>"#;
+
+        let links = DuckDuckGoSearch::links_from_html(html).unwrap();
+
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].url, "https://doc.rust-lang.org/book/");
+        assert_eq!(
+            links[0].title.as_deref(),
+            Some("The Rust Programming Language")
+        );
+        assert_eq!(
+            links[0].snippet.as_deref(),
+            Some("An introductory book about Rust.")
+        );
+        assert_eq!(links[0].engine.as_deref(), Some("duckduckgo"));
+        assert_eq!(links[1].title, None);
+        assert_eq!(links[1].snippet, None);
     }
 }
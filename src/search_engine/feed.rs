@@ -0,0 +1,355 @@
+use crate::errors::error::IsError;
+use crate::errors::error::IsError::Scrape;
+use crate::search_engine::link::{HtmlSource, Link};
+use crate::search_engine::redirect::resolve_url;
+use chrono::DateTime;
+use dashmap::{DashMap, DashSet};
+use once_cell::sync::Lazy;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use scraper::Html;
+
+// Summaries are shown in a single row of the feed list, so long ones are cut short.
+const MAX_SUMMARY_LENGTH: usize = 300;
+
+static FEEDS: Lazy<DashMap<String, Feed>> = Lazy::new(DashMap::new);
+static FORCED: Lazy<DashSet<String>> = Lazy::new(DashSet::new);
+
+/// An RSS or Atom feed, with each entry as a link to the page it is about.
+#[derive(Clone)]
+pub struct Feed {
+    pub title: String,
+    pub entries: Vec<Link>,
+}
+
+/// Shows the source as a feed, whatever content type it was sent with.
+pub fn force_feed(html_source: &HtmlSource) {
+    FORCED.insert(source_key(html_source));
+}
+
+pub fn is_forced(html_source: &HtmlSource) -> bool {
+    FORCED.contains(&source_key(html_source))
+}
+
+/// Records the feed read from the source, so its entries can be listed once the page is loaded.
+pub fn record_feed(html_source: &HtmlSource, feed: Feed) {
+    FEEDS.insert(source_key(html_source), feed);
+}
+
+/// The feed that was read from the source, or None if the source was not a feed.
+pub fn feed_for(html_source: &HtmlSource) -> Option<Feed> {
+    FEEDS.get(&source_key(html_source)).map(|feed| feed.clone())
+}
+
+fn source_key(html_source: &HtmlSource) -> String {
+    match html_source {
+        HtmlSource::LinkSource(link) => link.url.clone(),
+        HtmlSource::FileSource(file) => file.file_path.clone(),
+    }
+}
+
+/// Whether the document is an RSS, RDF or Atom feed, from the name of its root element.
+pub fn is_feed(xml: &str) -> bool {
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(element) | Event::Empty(element)) => {
+                return matches!(element.local_name().as_ref(), b"rss" | b"feed" | b"RDF");
+            }
+            Ok(Event::Eof) | Err(_) => return false,
+            Ok(_) => {}
+        }
+    }
+}
+
+/// Reads the entries from an RSS, RDF or Atom feed. Links in the feed are resolved against the
+/// url of the feed.
+pub fn parse_feed(url: &str, xml: &str) -> Result<Feed, IsError> {
+    let mut reader = Reader::from_str(xml);
+    let mut path: Vec<String> = vec![];
+    let mut title = String::new();
+    let mut entries = vec![];
+    // The entry being read, and where it is in the path.
+    let mut entry: Option<(usize, RawEntry)> = None;
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|err| Scrape(format!("Unable to read the feed {url}: {err}")))?;
+        match event {
+            Event::Start(element) => {
+                let name = local_name(&element);
+                start_element(&element, &name, path.len(), &mut entry);
+                path.push(name);
+            }
+            Event::Empty(element) => {
+                start_element(&element, &local_name(&element), path.len(), &mut entry);
+            }
+            Event::End(_) => {
+                path.pop();
+                if entry
+                    .as_ref()
+                    .is_some_and(|(depth, _)| *depth == path.len())
+                {
+                    if let Some((_, raw)) = entry.take() {
+                        entries.push(raw);
+                    }
+                }
+            }
+            Event::Text(text) => {
+                let text = text
+                    .unescape()
+                    .map_or_else(|_| String::from_utf8_lossy(&text).to_string(), |t| t.into());
+                add_text(&path, &text, &mut entry, &mut title);
+            }
+            Event::CData(text) => {
+                add_text(
+                    &path,
+                    &String::from_utf8_lossy(&text),
+                    &mut entry,
+                    &mut title,
+                );
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(Feed {
+        title: collapse_whitespace(&title),
+        entries: entries
+            .into_iter()
+            .filter_map(|raw| raw.into_link(url))
+            .collect(),
+    })
+}
+
+#[derive(Default)]
+struct RawEntry {
+    title: String,
+    link: String,
+    guid: String,
+    date: String,
+    updated: String,
+    summary: String,
+    content: String,
+}
+
+impl RawEntry {
+    // Entries without a link can't be opened, so these are not shown.
+    fn into_link(self, url: &str) -> Option<Link> {
+        let target = Some(self.link.trim())
+            .filter(|link| !link.is_empty())
+            .or_else(|| Some(self.guid.trim()).filter(|guid| guid.contains("://")))?;
+        let target = if url.is_empty() {
+            target.to_string()
+        } else {
+            resolve_url(url, target)
+        };
+        let summary = if self.summary.trim().is_empty() {
+            &self.content
+        } else {
+            &self.summary
+        };
+        // Atom entries may only have the time they were last updated.
+        let date = if self.date.trim().is_empty() {
+            &self.updated
+        } else {
+            &self.date
+        };
+        let title = collapse_whitespace(&strip_html(&self.title));
+        Some(Link {
+            title: Some(title).filter(|title| !title.is_empty()),
+            snippet: Some(summarize(summary)).filter(|summary| !summary.is_empty()),
+            published: Some(format_date(date)).filter(|date| !date.is_empty()),
+            ..Link::new(&target)
+        })
+    }
+}
+
+fn local_name(element: &BytesStart) -> String {
+    String::from_utf8_lossy(element.local_name().as_ref()).to_lowercase()
+}
+
+fn start_element(
+    element: &BytesStart,
+    name: &str,
+    depth: usize,
+    entry: &mut Option<(usize, RawEntry)>,
+) {
+    match (name, entry) {
+        ("item" | "entry", entry @ None) => *entry = Some((depth, RawEntry::default())),
+        // Atom links are in the href, and the page for the entry is the alternate link.
+        ("link", Some((entry_depth, raw))) if depth == *entry_depth + 1 => {
+            let attribute = |name: &str| {
+                element
+                    .try_get_attribute(name)
+                    .ok()
+                    .flatten()
+                    .and_then(|value| value.unescape_value().ok())
+                    .map(|value| value.to_string())
+            };
+            let alternate = attribute("rel").is_none_or(|rel| rel == "alternate");
+            if let Some(href) = attribute("href").filter(|_| alternate && raw.link.is_empty()) {
+                raw.link = href;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn add_text(
+    path: &[String],
+    text: &str,
+    entry: &mut Option<(usize, RawEntry)>,
+    feed_title: &mut String,
+) {
+    let Some((depth, raw)) = entry else {
+        if matches!(path, [.., parent, name] if name == "title" && (parent == "channel" || parent == "feed"))
+        {
+            feed_title.push_str(text);
+        }
+        return;
+    };
+    // Text in elements nested in a field, such as xhtml content, belongs to that field.
+    let field = match path.get(*depth + 1).map(String::as_str) {
+        Some("title") => &mut raw.title,
+        Some("link") => &mut raw.link,
+        Some("guid" | "id") => &mut raw.guid,
+        Some("pubdate" | "published" | "date" | "issued") => &mut raw.date,
+        Some("updated" | "modified") => &mut raw.updated,
+        Some("description" | "summary") => &mut raw.summary,
+        Some("content" | "encoded") => &mut raw.content,
+        _ => return,
+    };
+    field.push_str(text);
+}
+
+// Feed text is often html, which is shown as the text it contains.
+fn strip_html(text: &str) -> String {
+    if !text.contains('<') {
+        return text.to_string();
+    }
+    Html::parse_fragment(text)
+        .root_element()
+        .text()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn summarize(text: &str) -> String {
+    let summary = collapse_whitespace(&strip_html(text));
+    if summary.chars().count() <= MAX_SUMMARY_LENGTH {
+        return summary;
+    }
+    let cut = summary.chars().take(MAX_SUMMARY_LENGTH).collect::<String>();
+    format!("{}…", cut.trim_end())
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+// RSS dates are RFC 2822 and Atom dates RFC 3339, both shown as the date alone.
+fn format_date(date: &str) -> String {
+    let date = date.trim();
+    DateTime::parse_from_rfc2822(date)
+        .or_else(|_| DateTime::parse_from_rfc3339(date))
+        .map_or_else(
+            |_| date.to_string(),
+            |date| date.format("%Y-%m-%d").to_string(),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>Release Notes</title>
+    <link>https://example.com/</link>
+    <item>
+      <title>Version 1.2 &amp; more</title>
+      <link>https://example.com/releases/1.2</link>
+      <pubDate>Tue, 10 Jun 2025 04:00:00 GMT</pubDate>
+      <description><![CDATA[<p>Adds <b>feeds</b>.</p>]]></description>
+    </item>
+    <item>
+      <title>Version 1.1</title>
+      <guid isPermaLink="true">https://example.com/releases/1.1</guid>
+      <content:encoded>&lt;p&gt;Fixes&lt;/p&gt;</content:encoded>
+    </item>
+    <item>
+      <title>No link</title>
+    </item>
+  </channel>
+</rss>"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title type="text">Changelog</title>
+  <link href="https://example.org/"/>
+  <entry>
+    <title>Big release</title>
+    <link rel="replies" href="https://example.org/comments"/>
+    <link rel="alternate" href="/posts/big"/>
+    <updated>2025-03-01T10:00:00Z</updated>
+    <published>2025-02-28T09:00:00+01:00</published>
+    <content type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml"><p>All the <em>new</em> things</p></div></content>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn test_parse_rss() {
+        let feed = parse_feed("https://example.com/feed.xml", RSS).unwrap();
+
+        assert_eq!(feed.title, "Release Notes");
+        assert_eq!(feed.entries.len(), 2);
+        let first = &feed.entries[0];
+        assert_eq!(first.url, "https://example.com/releases/1.2");
+        assert_eq!(first.title.as_deref(), Some("Version 1.2 & more"));
+        assert_eq!(first.published.as_deref(), Some("2025-06-10"));
+        assert_eq!(first.snippet.as_deref(), Some("Adds feeds ."));
+        let second = &feed.entries[1];
+        assert_eq!(second.url, "https://example.com/releases/1.1");
+        assert_eq!(second.published, None);
+        assert_eq!(second.snippet.as_deref(), Some("Fixes"));
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let feed = parse_feed("https://example.org/atom", ATOM).unwrap();
+
+        assert_eq!(feed.title, "Changelog");
+        assert_eq!(feed.entries.len(), 1);
+        let entry = &feed.entries[0];
+        assert_eq!(entry.url, "https://example.org/posts/big");
+        assert_eq!(entry.title.as_deref(), Some("Big release"));
+        assert_eq!(entry.published.as_deref(), Some("2025-02-28"));
+        assert_eq!(entry.snippet.as_deref(), Some("All the new things"));
+    }
+
+    #[test]
+    fn test_is_feed() {
+        assert!(is_feed(RSS));
+        assert!(is_feed(ATOM));
+        assert!(is_feed(
+            r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"></rdf:RDF>"#
+        ));
+        assert!(!is_feed("<!DOCTYPE html><html><body>Page</body></html>"));
+        assert!(!is_feed(r#"{"feed": []}"#));
+    }
+
+    #[test]
+    fn test_long_summary_is_shortened() {
+        let summary = summarize(&"word ".repeat(100));
+
+        assert!(summary.ends_with("word…"));
+        assert_eq!(summary.chars().count(), MAX_SUMMARY_LENGTH);
+    }
+
+    #[test]
+    fn test_invalid_feed_errors() {
+        assert!(parse_feed("https://example.com/feed", "<rss><channel></item></rss>").is_err());
+    }
+}
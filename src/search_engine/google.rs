@@ -1,9 +1,13 @@
+use crate::cli::command::SafeSearch;
+use crate::config::load::Config;
 use crate::errors::error::IsError;
 use crate::errors::error::IsError::Search as SearchError;
 use crate::search_engine::link::HtmlSource::LinkSource;
 use crate::search_engine::link::Link;
+use crate::search_engine::locale::SearchLocale;
 use crate::search_engine::scrape::scrape;
 use crate::search_engine::search_type::Search;
+use once_cell::sync::Lazy;
 use serde_json::from_str;
 
 #[derive(serde::Deserialize)]
@@ -14,6 +18,10 @@ struct SearchResult {
 #[derive(serde::Deserialize)]
 struct SearchItem {
     link: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    snippet: String,
 }
 
 #[derive(Debug, Clone)]
@@ -21,6 +29,11 @@ pub struct GoogleSearch;
 
 const API_KEY: &str = "IS_FAST_GOOGLE_API_KEY";
 const SEARCH_ENGINE_ID: &str = "IS_FAST_GOOGLE_SEARCH_ENGINE_ID";
+pub(crate) const ENGINE_NAME: &str = "google";
+// The custom search api returns at most 10 results at a time.
+const PAGE_SIZE: usize = 10;
+
+static LOCALE: Lazy<&SearchLocale> = Lazy::new(Config::get_search_locale);
 
 impl GoogleSearch {
     fn extract_variables() -> Result<(String, String), IsError> {
@@ -40,27 +53,79 @@ impl GoogleSearch {
         api_key: &str,
         search_engine_id: &str,
         query: &str,
+        page: usize,
     ) -> Result<Vec<Link>, IsError> {
-        scrape(&LinkSource(Link::new(&format!(
-            "https://www.googleapis.com/customsearch/v1?key={api_key}&cx={search_engine_id}&q={query}",
+        scrape(&LinkSource(Link::new(&Self::search_url(
+            api_key,
+            search_engine_id,
+            query,
+            page,
+            &LOCALE,
         ))))
         .and_then(|json| from_str::<SearchResult>(&json).map_err(|e| SearchError(e.to_string())))
         .map(|search_result| Self::search_result_to_links(&search_result))
         .map_err(|e| SearchError(e.to_string()))
     }
 
+    // The custom search api only has safe search on or off, so moderate also turns it on.
+    fn search_url(
+        api_key: &str,
+        search_engine_id: &str,
+        query: &str,
+        page: usize,
+        locale: &SearchLocale,
+    ) -> String {
+        let start = page * PAGE_SIZE + 1;
+        let mut url = format!(
+            "https://www.googleapis.com/customsearch/v1?key={api_key}&cx={search_engine_id}&q={query}&start={start}",
+        );
+        if let Some(region) = &locale.region {
+            let region = if region == "gb" { "uk" } else { region };
+            url.push_str(&format!("&gl={region}"));
+        }
+        if let Some(language) = &locale.language {
+            url.push_str(&format!("&lr=lang_{language}"));
+        }
+        if let Some(safe_search) = &locale.safe_search {
+            let safe = match safe_search {
+                SafeSearch::Off => "off",
+                SafeSearch::Moderate | SafeSearch::Strict => "active",
+            };
+            url.push_str(&format!("&safe={safe}"));
+        }
+        url
+    }
+
     fn search_result_to_links(search_result: &SearchResult) -> Vec<Link> {
         search_result
             .items
             .iter()
-            .map(|item| Link::new(&item.link))
+            .map(|item| Link::from_search(&item.link, &item.title, &item.snippet, ENGINE_NAME))
             .collect()
     }
 }
 impl Search for GoogleSearch {
-    fn search(&self, query: &str) -> Result<Vec<Link>, IsError> {
+    fn search(&self, query: &str, page: usize) -> Result<Vec<Link>, IsError> {
         Self::extract_variables().and_then(|(api_key, search_engine_id)| {
-            Self::extract_links(&api_key, &search_engine_id, query)
+            Self::extract_links(&api_key, &search_engine_id, query, page)
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_url_with_locale() {
+        let locale = SearchLocale::new(
+            Some(String::from("gb")),
+            Some(String::from("en")),
+            Some(SafeSearch::Moderate),
+        );
+        assert_eq!(
+            GoogleSearch::search_url("key", "id", "rust", 1, &locale),
+            "https://www.googleapis.com/customsearch/v1?key=key&cx=id&q=rust&start=11&gl=uk&lr=lang_en&safe=active"
+        );
+    }
+}
@@ -1,8 +1,9 @@
+use crate::config::load::Config;
 use crate::errors::error::IsError;
-use crate::errors::error::IsError::Scrape;
 use crate::errors::error::IsError::Search as SearchError;
 use crate::search_engine::link::Link;
-use crate::search_engine::scrape::UREQ_AGENT;
+use crate::search_engine::locale::SearchLocale;
+use crate::search_engine::scrape::get_text;
 use crate::search_engine::search_type::Search;
 use serde_json::from_str;
 
@@ -16,12 +17,20 @@ struct SearchItem {
     t: i32,
     #[serde(default)]
     url: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    snippet: String,
 }
 
 #[derive(Debug, Clone)]
 pub struct KagiSearch;
 
 const API_KEY: &str = "IS_FAST_KAGI_API_KEY";
+pub(crate) const ENGINE_NAME: &str = "kagi";
+// The api has no offset, so later pages are loaded by raising the limit and skipping the results
+// that were already shown.
+const PAGE_SIZE: usize = 10;
 
 impl KagiSearch {
     fn extract_variables() -> Result<String, IsError> {
@@ -32,49 +41,40 @@ impl KagiSearch {
         Ok(api_key)
     }
 
-    fn search_result_to_links(search_result: &SearchResult) -> Vec<Link> {
+    fn search_result_to_links(search_result: &SearchResult, page: usize) -> Vec<Link> {
         search_result
             .data
             .iter()
             .filter(|item| item.t == 0)
-            .map(|item| Link::new(&item.url))
+            .skip(page * PAGE_SIZE)
+            .map(|item| Link::from_search(&item.url, &item.title, &item.snippet, ENGINE_NAME))
             .collect()
     }
 
-    fn request_results(api_key: &str, query: &str) -> Result<String, IsError> {
-        let url = format!("https://kagi.com/api/v0/search?q={query}");
-        UREQ_AGENT
-            .get(&url)
-            .header("Authorization", &format!("Bot {api_key}"))
-            .call()
-            .map_err(|e| Scrape(format!("Request failed for {url}: {e}")))
-            .and_then(|response| {
-                if response.status().is_success() {
-                    response
-                        .into_body()
-                        .read_to_string()
-                        .map_err(|e| Scrape(format!("Failed to read response body for {url}: {e}")))
-                } else {
-                    Err(Scrape(format!(
-                        "Request failed for {url}: HTTP Status {}",
-                        response.status()
-                    )))
-                }
-            })
+    fn request_results(api_key: &str, query: &str, page: usize) -> Result<String, IsError> {
+        let url = format!(
+            "https://kagi.com/api/v0/search?q={query}&limit={}",
+            (page + 1) * PAGE_SIZE
+        );
+        get_text(&url, &[("Authorization", &format!("Bot {api_key}"))])
     }
 
-    fn get_links(api_key: &str, query: &str) -> Result<Vec<Link>, IsError> {
-        Self::request_results(api_key, query)
+    fn get_links(api_key: &str, query: &str, page: usize) -> Result<Vec<Link>, IsError> {
+        Self::request_results(api_key, query, page)
             .and_then(|json| {
                 from_str::<SearchResult>(&json).map_err(|e| SearchError(e.to_string()))
             })
-            .map(|search_result| Self::search_result_to_links(&search_result))
+            .map(|search_result| Self::search_result_to_links(&search_result, page))
             .map_err(|e| SearchError(e.to_string()))
     }
 }
 
 impl Search for KagiSearch {
-    fn search(&self, query: &str) -> Result<Vec<Link>, IsError> {
-        Self::extract_variables().and_then(|api_key| Self::get_links(&api_key, query))
+    fn search(&self, query: &str, page: usize) -> Result<Vec<Link>, IsError> {
+        if Config::get_search_locale() != &SearchLocale::default() {
+            // The search api has no region or safe search parameters.
+            log::debug!("Kagi searches with the region and safe search settings of the account");
+        }
+        Self::extract_variables().and_then(|api_key| Self::get_links(&api_key, query, page))
     }
 }
@@ -1,6 +1,16 @@
 #[cfg(not(test))]
 use crate::config::load::Config;
 use crate::config::site::SiteConfig;
+use crate::search_engine::redirect::final_url;
+use once_cell::sync::OnceCell;
+use std::fs;
+use std::io;
+use std::io::Read;
+
+/// The path given to `--file` to read the page from stdin.
+pub const STDIN_PATH: &str = "-";
+
+static STDIN: OnceCell<Vec<u8>> = OnceCell::new();
 
 #[derive(Clone)]
 pub enum HtmlSource {
@@ -16,23 +26,76 @@ impl HtmlSource {
         }
     }
 
+    /// The url of the page that was shown, after following any redirects from the url.
+    pub fn get_final_url(&self) -> String {
+        final_url(self.get_url())
+    }
+
+    pub fn is_cached(&self) -> bool {
+        self.get_link().is_some_and(|link| link.cached)
+    }
+
+    pub fn get_link(&self) -> Option<&Link> {
+        match self {
+            HtmlSource::LinkSource(link) => Some(link),
+            HtmlSource::FileSource(_) => None,
+        }
+    }
+
     #[cfg(not(test))]
     pub fn get_config(&self) -> SiteConfig {
-        Config::get_site_config(self.get_url()).clone()
+        Config::get_site_config(&self.get_final_url()).clone()
     }
 }
 
 #[derive(Clone)]
 pub struct Link {
     pub url: String,
+    pub title: Option<String>,
+    pub snippet: Option<String>,
+    pub engine: Option<String>,
+    // The date a feed entry was published.
+    pub published: Option<String>,
+    pub cached: bool,
 }
 impl Link {
     pub fn new(url: &str) -> Self {
         Self {
             url: Self::format_url(url),
+            title: None,
+            snippet: None,
+            engine: None,
+            published: None,
+            cached: false,
         }
     }
 
+    /// A link returned by a search engine, keeping the details the engine gave for the result.
+    pub fn from_search(url: &str, title: &str, snippet: &str, engine: &str) -> Self {
+        Self {
+            url: Self::format_url(url),
+            title: Self::non_empty(title),
+            snippet: Self::non_empty(snippet),
+            engine: Self::non_empty(engine),
+            published: None,
+            cached: false,
+        }
+    }
+
+    /// A link found in the local cache, which is always read from the cache whatever the cache
+    /// mode is, so that it can be viewed offline.
+    pub fn from_cache(url: &str, title: &str, snippet: &str, engine: &str) -> Self {
+        Self {
+            cached: true,
+            ..Self::from_search(url, title, snippet, engine)
+        }
+    }
+
+    fn non_empty(value: &str) -> Option<String> {
+        let value = value.split_whitespace().collect::<Vec<&str>>().join(" ");
+        Some(value).filter(|v| !v.is_empty())
+    }
+
     fn format_url(url: &str) -> String {
         if url.starts_with("http") {
             return url.to_string();
@@ -53,7 +116,32 @@ impl File {
             associated_url,
         }
     }
+
+    pub fn is_stdin(&self) -> bool {
+        self.file_path == STDIN_PATH
+    }
+
+    /// Reads the file, or everything piped to stdin when the path is `-`.
+    pub fn read(&self) -> io::Result<Vec<u8>> {
+        if self.is_stdin() {
+            read_buffered(&STDIN, io::stdin())
+        } else {
+            fs::read(&self.file_path)
+        }
+    }
 }
+
+// Stdin can only be read once, but the page can be extracted again, so the input is kept.
+fn read_buffered(buffer: &OnceCell<Vec<u8>>, mut reader: impl Read) -> io::Result<Vec<u8>> {
+    buffer
+        .get_or_try_init(|| {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes)?;
+            Ok(bytes)
+        })
+        .cloned()
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -69,4 +157,27 @@ pub mod tests {
             TEST_CONFIG.read().clone()
         }
     }
+
+    #[test]
+    fn test_stdin_is_read_once() {
+        let buffer = OnceCell::new();
+
+        assert_eq!(
+            read_buffered(&buffer, "<p>piped</p>".as_bytes()).unwrap(),
+            b"<p>piped</p>"
+        );
+        assert_eq!(
+            read_buffered(&buffer, "".as_bytes()).unwrap(),
+            b"<p>piped</p>"
+        );
+    }
+
+    #[test]
+    fn test_file_is_read_from_path() {
+        let file = File::new(String::from("tests/data/sample.txt"), String::new());
+
+        assert!(!file.is_stdin());
+        assert!(file.read().unwrap().starts_with(b"Plain text"));
+        assert!(File::new(String::from(STDIN_PATH), String::new()).is_stdin());
+    }
 }
@@ -0,0 +1,101 @@
+use crate::database::history_database::search_history;
+use crate::errors::error::IsError;
+use crate::errors::error::IsError::Search as SearchError;
+use crate::search_engine::cache::cached_pages_search;
+use crate::search_engine::link::Link;
+use crate::search_engine::search::normalize_url;
+use crate::search_engine::search_type::Search;
+use std::collections::HashSet;
+
+pub(crate) const ENGINE_NAME: &str = "local";
+const PAGE_SIZE: usize = 20;
+
+/// Searches the pages that are already in the cache, and the titles of the visited pages in the
+/// history, so pages can be found without a connection. Cached pages are always opened from the
+/// cache.
+#[derive(Debug, Clone)]
+pub struct LocalSearch;
+
+impl LocalSearch {
+    // The query is joined with + for the web engines, and can have site: terms added. The sites are
+    // checked once the search is done, so these are dropped here. Each word is quoted so it is
+    // never read as full text search syntax.
+    fn full_text_query(query: &str) -> Option<String> {
+        let words = query
+            .split(|c: char| c == '+' || c.is_whitespace())
+            .filter(|word| !word.is_empty() && !word.contains(':') && *word != "OR")
+            .map(|word| format!("\"{}\"", word.replace('"', "")))
+            .filter(|word| word != "\"\"")
+            .collect::<Vec<String>>();
+        if words.is_empty() {
+            None
+        } else {
+            Some(words.join(" "))
+        }
+    }
+
+    // The cached pages are still shown when the history can't be searched.
+    fn history_links(query: &str, found: &[Link]) -> Vec<Link> {
+        let found = found
+            .iter()
+            .map(|link| normalize_url(&link.url))
+            .collect::<HashSet<String>>();
+        search_history(query)
+            .unwrap_or_else(|e| {
+                log::warn!("Skipping the history, as it could not be searched: {e:?}");
+                vec![]
+            })
+            .into_iter()
+            .filter(|history| !found.contains(&normalize_url(&history.url)))
+            .map(|history| Link::from_search(&history.url, &history.title, "", ENGINE_NAME))
+            .collect()
+    }
+}
+
+impl Search for LocalSearch {
+    fn search(&self, query: &str, page: usize) -> Result<Vec<Link>, IsError> {
+        let query = Self::full_text_query(query)
+            .ok_or_else(|| SearchError(String::from("No words to search for")))?;
+        let mut links = cached_pages_search(&query, PAGE_SIZE, page * PAGE_SIZE)
+            .into_iter()
+            .map(|page| Link::from_cache(&page.url, &page.title, &page.snippet, ENGINE_NAME))
+            .collect::<Vec<Link>>();
+        // Visited pages that are not cached can only be opened with a connection, so these come
+        // after the cached pages on the first page.
+        if page == 0 {
+            let history = Self::history_links(&query, &links);
+            links.extend(history);
+        }
+        if links.is_empty() {
+            Err(SearchError(String::from(
+                "No cached or visited pages matched the search",
+            )))
+        } else {
+            Ok(links)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_text_query_quotes_words() {
+        assert_eq!(
+            LocalSearch::full_text_query("baeldung+spring boot"),
+            Some(String::from("\"baeldung\" \"spring\" \"boot\""))
+        );
+    }
+
+    #[test]
+    fn test_full_text_query_drops_site_terms_and_syntax() {
+        assert_eq!(
+            LocalSearch::full_text_query(
+                "rust+site:docs.rs+OR+site:crates.io+-site:x.com+\"NEAR\""
+            ),
+            Some(String::from("\"rust\" \"NEAR\""))
+        );
+        assert_eq!(LocalSearch::full_text_query("site:docs.rs"), None);
+    }
+}
@@ -0,0 +1,30 @@
+use crate::cli::command::SafeSearch;
+
+/// The region, language and safe search level to search with. Each engine translates these into
+/// its own parameters, and anything not set is left to the engine to decide.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchLocale {
+    pub(crate) region: Option<String>,
+    pub(crate) language: Option<String>,
+    pub(crate) safe_search: Option<SafeSearch>,
+}
+
+impl SearchLocale {
+    pub fn new(
+        region: Option<String>,
+        language: Option<String>,
+        safe_search: Option<SafeSearch>,
+    ) -> Self {
+        Self {
+            region: normalize_code(region),
+            language: normalize_code(language),
+            safe_search,
+        }
+    }
+}
+
+// Codes are given as two letter country and language codes, e.g. region = "gb", language = "en".
+fn normalize_code(code: Option<String>) -> Option<String> {
+    code.map(|code| code.trim().to_lowercase())
+        .filter(|code| !code.is_empty())
+}
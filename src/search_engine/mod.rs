@@ -1,8 +1,24 @@
+pub mod auth;
 pub mod cache;
+pub mod cookies;
+pub mod domain_filter;
 mod duckduckgo;
+pub mod feed;
 mod google;
 pub mod kagi;
 pub mod link;
+pub mod local;
+pub mod locale;
+pub mod proxy;
+pub mod redirect;
+pub mod retry;
 pub mod scrape;
 pub mod search;
 pub mod search_type;
+pub mod searxng;
+pub mod template;
+#[cfg(test)]
+pub mod test_server;
+pub mod throttle;
+pub mod tls;
+pub mod wikipedia;
@@ -0,0 +1,196 @@
+use crate::config::site::CallConfig;
+use crate::errors::error::IsError;
+use crate::errors::error::IsError::Scrape;
+use std::env;
+use ureq::http::Uri;
+use ureq::Proxy;
+
+/// Finds the proxy to use for the url. The proxy and no_proxy settings for the site are used when
+/// they are set, otherwise the standard proxy environment variables are used.
+pub fn proxy_for(url: &str, call: &CallConfig) -> Result<Option<Proxy>, IsError> {
+    resolve_proxy(url, call, |name| env::var(name).ok())
+        .map(|proxy| {
+            Proxy::new(&proxy).map_err(|e| Scrape(format!("Invalid proxy {proxy} for {url}: {e}")))
+        })
+        .transpose()
+}
+
+fn resolve_proxy(
+    url: &str,
+    call: &CallConfig,
+    env: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    let uri = url.parse::<Uri>().ok()?;
+    let host = uri.host()?.to_lowercase();
+    let no_proxy = call.get_no_proxy().map_or_else(
+        || {
+            env_var(&env, "NO_PROXY")
+                .map(|no_proxy| no_proxy.split(',').map(str::to_string).collect())
+                .unwrap_or_default()
+        },
+        <[String]>::to_vec,
+    );
+    if no_proxy.iter().any(|entry| bypasses(&host, entry)) {
+        log::debug!("Not using a proxy for {url}");
+        return None;
+    }
+    let scheme_var = if uri.scheme_str() == Some("http") {
+        "HTTP_PROXY"
+    } else {
+        "HTTPS_PROXY"
+    };
+    call.get_proxy()
+        .map(str::to_string)
+        .or_else(|| env_var(&env, scheme_var))
+        .or_else(|| env_var(&env, "ALL_PROXY"))
+        .inspect(|proxy| log::debug!("Using proxy {proxy} for {url}"))
+}
+
+// The variables are used in both upper and lower case, with the upper case taking priority.
+fn env_var(env: impl Fn(&str) -> Option<String>, name: &str) -> Option<String> {
+    env(name)
+        .or_else(|| env(&name.to_lowercase()))
+        .filter(|value| !value.trim().is_empty())
+}
+
+// Entries follow the usual NO_PROXY format: * for every host, or a domain that also covers its
+// subdomains, with an optional leading . or *. and port.
+fn bypasses(host: &str, entry: &str) -> bool {
+    let entry = entry.trim().to_lowercase();
+    if entry == "*" {
+        return true;
+    }
+    let entry = entry
+        .trim_start_matches("*.")
+        .trim_start_matches('.')
+        .split(':')
+        .next()
+        .unwrap_or_default();
+    !entry.is_empty() && (host == entry || host.ends_with(&format!(".{entry}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_engine::scrape::get_request;
+    use crate::search_engine::test_server::serve_proxy_once;
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<HashMap<String, String>>();
+        move |name| vars.get(name).cloned()
+    }
+
+    fn call(proxy: Option<&str>, no_proxy: Option<&[&str]>) -> CallConfig {
+        CallConfig {
+            proxy: proxy.map(str::to_string),
+            no_proxy: no_proxy
+                .map(|entries| entries.iter().map(|entry| entry.to_string()).collect()),
+            ..CallConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_config_proxy_overrides_environment() {
+        let env = env(&[("HTTPS_PROXY", "http://env-proxy:8080")]);
+        assert_eq!(
+            resolve_proxy(
+                "https://docs.rs",
+                &call(Some("socks5://localhost:1080"), None),
+                env
+            ),
+            Some(String::from("socks5://localhost:1080"))
+        );
+    }
+
+    #[test]
+    fn test_environment_proxy_for_scheme() {
+        let env = env(&[
+            ("http_proxy", "http://http-proxy:8080"),
+            ("HTTPS_PROXY", "http://https-proxy:8080"),
+        ]);
+        assert_eq!(
+            resolve_proxy("http://docs.rs", &call(None, None), &env),
+            Some(String::from("http://http-proxy:8080"))
+        );
+        assert_eq!(
+            resolve_proxy("https://docs.rs", &call(None, None), &env),
+            Some(String::from("http://https-proxy:8080"))
+        );
+        assert_eq!(
+            resolve_proxy(
+                "https://docs.rs",
+                &call(None, None),
+                self::env(&[("ALL_PROXY", "socks5://all:1080")])
+            ),
+            Some(String::from("socks5://all:1080"))
+        );
+        assert_eq!(
+            resolve_proxy("https://docs.rs", &call(None, None), self::env(&[])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_no_proxy_bypasses_matching_hosts() {
+        let env = env(&[
+            ("HTTPS_PROXY", "http://proxy:8080"),
+            (
+                "NO_PROXY",
+                "localhost, .corp.example.com,wiki.internal:8443",
+            ),
+        ]);
+        let no_config = call(None, None);
+        assert_eq!(
+            resolve_proxy("https://localhost:3000", &no_config, &env),
+            None
+        );
+        assert_eq!(
+            resolve_proxy("https://docs.corp.example.com/page", &no_config, &env),
+            None
+        );
+        assert_eq!(
+            resolve_proxy("https://wiki.internal/page", &no_config, &env),
+            None
+        );
+        assert_eq!(
+            resolve_proxy("https://example.com", &no_config, &env),
+            Some(String::from("http://proxy:8080"))
+        );
+        // The configured list replaces the environment list.
+        let configured = call(Some("http://proxy:8080"), Some(&["*"]));
+        assert_eq!(
+            resolve_proxy("https://example.com", &configured, &env),
+            None
+        );
+    }
+
+    #[test]
+    fn test_request_is_sent_through_proxy() {
+        let (proxy, connect) = serve_proxy_once("text/html", b"<p>Through the proxy</p>");
+
+        // Nothing listens on the discard port, so the page can only come from the proxy. The empty
+        // no_proxy list stops a NO_PROXY set in the environment from bypassing it.
+        let response = get_request("http://127.0.0.1:9/page", &call(Some(&proxy), Some(&[])))
+            .unwrap()
+            .call()
+            .unwrap();
+
+        assert_eq!(
+            response.into_body().read_to_string().unwrap(),
+            "<p>Through the proxy</p>"
+        );
+        assert_eq!(connect.recv().unwrap(), "CONNECT 127.0.0.1:9 HTTP/1.1");
+    }
+
+    #[test]
+    fn test_invalid_proxy_errors() {
+        assert!(matches!(
+            proxy_for("https://docs.rs", &call(Some("ftp://proxy"), None)),
+            Err(Scrape(_))
+        ));
+    }
+}
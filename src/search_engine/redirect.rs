@@ -0,0 +1,268 @@
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use scraper::{Html, Selector};
+use ureq::http::Uri;
+
+pub const DEFAULT_MAX_HOPS: usize = 5;
+
+// Refreshes that wait longer than this are treated as the page reloading itself, not a redirect.
+const MAX_REFRESH_DELAY: u64 = 10;
+// Redirect stubs are small, so larger pages are never checked for a lone link.
+const MAX_STUB_LENGTH: usize = 4096;
+const MAX_STUB_TEXT: usize = 200;
+
+static FINAL_URLS: Lazy<DashMap<String, String>> = Lazy::new(DashMap::new);
+static META_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("meta").expect("invalid redirect selector"));
+static CANONICAL_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("link[rel~=canonical][href]").expect("invalid redirect selector"));
+static AMP_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("html[amp], html[⚡]").expect("invalid redirect selector"));
+static LINK_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("a[href]").expect("invalid redirect selector"));
+static BODY_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("body").expect("invalid redirect selector"));
+
+/// How many redirects in the page itself are followed, and whether the canonical url of every
+/// page is followed rather than only for AMP pages.
+#[derive(Debug, Clone)]
+pub struct RedirectSettings {
+    max_hops: usize,
+    follow_canonical: bool,
+}
+
+impl Default for RedirectSettings {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_HOPS, false)
+    }
+}
+
+impl RedirectSettings {
+    pub fn new(max_hops: usize, follow_canonical: bool) -> Self {
+        Self {
+            max_hops,
+            follow_canonical,
+        }
+    }
+
+    pub fn get_max_hops(&self) -> usize {
+        self.max_hops
+    }
+}
+
+/// Records the page that was shown for the url after following its redirects.
+pub fn record_final_url(url: &str, final_url: &str) {
+    // The final url can differ only by the trailing slash added to an empty path.
+    if url.trim_end_matches('/') != final_url.trim_end_matches('/') {
+        log::debug!("Recording {final_url} as the final url for {url}");
+        FINAL_URLS.insert(url.to_string(), final_url.to_string());
+    }
+}
+
+/// The page that was shown for the url, or the url itself if it was not redirected.
+pub fn final_url(url: &str) -> String {
+    FINAL_URLS
+        .get(url)
+        .map_or_else(|| url.to_string(), |final_url| final_url.clone())
+}
+
+/// Finds the page that this page redirects to without javascript - with a meta refresh, as a stub
+/// with a single link, or as an AMP page with its canonical url.
+pub fn redirect_target(url: &str, html: &str, settings: &RedirectSettings) -> Option<String> {
+    let head = Html::parse_document(&html[..head_end(html)]);
+    let follow_canonical = settings.follow_canonical || head.select(&AMP_SELECTOR).next().is_some();
+    meta_refresh(&head)
+        .or_else(|| follow_canonical.then(|| canonical(&head)).flatten())
+        .or_else(|| {
+            (html.len() <= MAX_STUB_LENGTH)
+                .then(|| stub_link(html))
+                .flatten()
+        })
+        .map(|target| resolve_url(url, &target))
+        .filter(|target| target.trim_end_matches('/') != url.trim_end_matches('/'))
+}
+
+// Only the head is needed for the meta tags, so the rest of a large page is not parsed.
+fn head_end(html: &str) -> usize {
+    html.to_ascii_lowercase()
+        .find("</head>")
+        .map_or(html.len(), |end| end + "</head>".len())
+}
+
+// The content is the delay in seconds, optionally followed by the url, such as
+// `0; url='/new-page'`.
+fn meta_refresh(html: &Html) -> Option<String> {
+    html.select(&META_SELECTOR)
+        .filter(|meta| {
+            meta.attr("http-equiv")
+                .is_some_and(|value| value.eq_ignore_ascii_case("refresh"))
+        })
+        .find_map(|meta| {
+            let (delay, target) = meta.attr("content")?.split_once([';', ','])?;
+            let delay = delay.trim().split('.').next()?.parse::<u64>().ok()?;
+            let target = target.trim();
+            let target = target
+                .get(..4)
+                .filter(|prefix| prefix.eq_ignore_ascii_case("url="))
+                .map_or(target, |_| &target[4..]);
+            let target = target.trim().trim_matches(['\'', '"']).trim();
+            (delay <= MAX_REFRESH_DELAY && !target.is_empty()).then(|| target.to_string())
+        })
+}
+
+fn canonical(html: &Html) -> Option<String> {
+    html.select(&CANONICAL_SELECTOR)
+        .filter_map(|link| link.attr("href"))
+        .map(str::trim)
+        .find(|href| !href.is_empty())
+        .map(str::to_string)
+}
+
+// A stub is a page with little more than a line saying it has moved, and a link to where.
+fn stub_link(html: &str) -> Option<String> {
+    let html = Html::parse_document(html);
+    let body = html.select(&BODY_SELECTOR).next()?;
+    let text = body.text().collect::<Vec<&str>>().join(" ").to_lowercase();
+    let mut links = body
+        .select(&LINK_SELECTOR)
+        .filter_map(|link| link.attr("href"))
+        .map(str::trim)
+        .filter(|href| !href.is_empty() && !href.starts_with('#'));
+    let link = links.next()?;
+    (links.next().is_none()
+        && text.split_whitespace().collect::<String>().len() <= MAX_STUB_TEXT
+        && ["redirect", "moved", "click here"]
+            .iter()
+            .any(|word| text.contains(word)))
+    .then(|| link.to_string())
+}
+
+/// Resolves a link found in a page against the url of the page.
+pub fn resolve_url(base: &str, link: &str) -> String {
+    if link.contains("://") {
+        return link.to_string();
+    }
+    let Ok(uri) = base.parse::<Uri>() else {
+        return link.to_string();
+    };
+    let scheme = uri.scheme_str().unwrap_or("https");
+    if let Some(rest) = link.strip_prefix("//") {
+        return format!("{scheme}://{rest}");
+    }
+    let origin = format!(
+        "{scheme}://{}",
+        uri.authority().map_or("", |authority| authority.as_str())
+    );
+    if link.starts_with('/') {
+        format!("{origin}{link}")
+    } else if link.starts_with('?') {
+        format!("{origin}{}{link}", uri.path())
+    } else {
+        // The link is relative to the directory of the page, with any . and .. segments removed.
+        let path = uri.path();
+        let mut segments = path.split('/').collect::<Vec<&str>>();
+        segments.pop();
+        for segment in link.split('/') {
+            match segment {
+                "." => {}
+                ".." => {
+                    if segments.len() > 1 {
+                        segments.pop();
+                    }
+                }
+                segment => segments.push(segment),
+            }
+        }
+        format!("{origin}{}", segments.join("/"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://example.com/docs/page";
+
+    fn target(html: &str) -> Option<String> {
+        redirect_target(URL, html, &RedirectSettings::default())
+    }
+
+    #[test]
+    fn test_meta_refresh_is_followed() {
+        assert_eq!(
+            target(r#"<head><meta http-equiv="Refresh" content="0; URL='/new/page'"></head>"#),
+            Some(String::from("https://example.com/new/page"))
+        );
+        assert_eq!(
+            target(r#"<meta http-equiv="refresh" content="3;url=https://other.com/">"#),
+            Some(String::from("https://other.com/"))
+        );
+    }
+
+    #[test]
+    fn test_slow_or_self_refresh_is_ignored() {
+        assert_eq!(
+            target(r#"<meta http-equiv="refresh" content="300; url=/new/page">"#),
+            None
+        );
+        assert_eq!(target(r#"<meta http-equiv="refresh" content="30">"#), None);
+        assert_eq!(
+            target(r#"<meta http-equiv="refresh" content="0; url=page">"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_canonical_is_only_followed_when_enabled() {
+        let html = r#"<head><link rel="canonical" href="https://example.com/real"></head><body><p>Text</p></body>"#;
+
+        assert_eq!(target(html), None);
+        assert_eq!(
+            redirect_target(URL, html, &RedirectSettings::new(5, true)),
+            Some(String::from("https://example.com/real"))
+        );
+    }
+
+    #[test]
+    fn test_amp_page_follows_canonical() {
+        let html = r#"<html amp><head><link rel="canonical" href="/real"></head><body><p>Text</p></body></html>"#;
+
+        assert_eq!(target(html), Some(String::from("https://example.com/real")));
+    }
+
+    #[test]
+    fn test_redirect_stub_link_is_followed() {
+        let stub = r#"<html><body><p>This page has moved. <a href="../moved">Click here</a></p></body></html>"#;
+        let page = r#"<html><body><p>An article, with <a href="/a">a link</a>.</p></body></html>"#;
+
+        assert_eq!(
+            target(stub),
+            Some(String::from("https://example.com/moved"))
+        );
+        assert_eq!(target(page), None);
+    }
+
+    #[test]
+    fn test_resolve_url() {
+        assert_eq!(resolve_url(URL, "//cdn.com/a"), "https://cdn.com/a");
+        assert_eq!(resolve_url(URL, "/a"), "https://example.com/a");
+        assert_eq!(resolve_url(URL, "other"), "https://example.com/docs/other");
+        assert_eq!(resolve_url(URL, "./a/../b"), "https://example.com/docs/b");
+        assert_eq!(
+            resolve_url(URL, "?q=1"),
+            "https://example.com/docs/page?q=1"
+        );
+        assert_eq!(
+            resolve_url("http://example.com", "page"),
+            "http://example.com/page"
+        );
+    }
+
+    #[test]
+    fn test_final_url_defaults_to_url() {
+        record_final_url("https://short.link/a", "https://example.com/a");
+
+        assert_eq!(final_url("https://short.link/a"), "https://example.com/a");
+        assert_eq!(final_url("https://short.link/b"), "https://short.link/b");
+    }
+}
@@ -0,0 +1,230 @@
+use crate::errors::error::IsError;
+use crate::errors::error::IsError::Scrape;
+use chrono::{DateTime, Utc};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::thread;
+use std::time::Duration;
+use ureq::http::Response;
+use ureq::typestate::WithoutBody;
+use ureq::{Body, Error, RequestBuilder};
+
+pub const DEFAULT_RETRIES: u32 = 2;
+pub const DEFAULT_RETRY_BASE_DELAY: u64 = 500;
+pub const DEFAULT_RETRY_JITTER: u64 = 250;
+
+// A site asking to wait longer than this is treated as down rather than waited on.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// How many times a failed request is retried, and how long to wait between the attempts. The
+/// delay doubles with each retry, with a random jitter added so retries are spread out.
+#[derive(Debug, Clone)]
+pub struct RetrySettings {
+    retries: u32,
+    base_delay: Duration,
+    jitter: Duration,
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_RETRIES,
+            DEFAULT_RETRY_BASE_DELAY,
+            DEFAULT_RETRY_JITTER,
+        )
+    }
+}
+
+impl RetrySettings {
+    pub fn new(retries: u32, base_delay_ms: u64, jitter_ms: u64) -> Self {
+        Self {
+            retries,
+            base_delay: Duration::from_millis(base_delay_ms),
+            jitter: Duration::from_millis(jitter_ms),
+        }
+    }
+
+    fn backoff(&self, retry: u32) -> Duration {
+        let jitter = if self.jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            let random = RandomState::new().build_hasher().finish();
+            Duration::from_millis(random % (self.jitter.as_millis() as u64 + 1))
+        };
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .saturating_add(jitter)
+    }
+}
+
+/// Sends the request, retrying connection errors, timeouts, 429 and 5xx responses. A new request
+/// is created for each attempt. When the retries run out on an error status, the last response is
+/// returned for the caller to handle like any other failed response.
+pub fn call_with_retry(
+    url: &str,
+    settings: &RetrySettings,
+    request: impl Fn() -> Result<RequestBuilder<WithoutBody>, IsError>,
+) -> Result<Response<Body>, IsError> {
+    let attempts = settings.retries + 1;
+    let mut attempt = 1;
+    let result = loop {
+        let result = request()?.call();
+        let retry = match &result {
+            Ok(response) if is_retryable_status(response.status().as_u16()) => Some((
+                format!("HTTP Status {}", response.status()),
+                retry_after(response),
+            )),
+            Err(e) if is_retryable_error(e) => Some((e.to_string(), None)),
+            _ => None,
+        };
+        let Some((reason, retry_after)) = retry else {
+            break result;
+        };
+        if attempt >= attempts {
+            log::warn!("Request for {url} failed after {attempt} attempts: {reason}");
+            break result;
+        }
+        if retry_after.is_some_and(|delay| delay > MAX_RETRY_AFTER) {
+            log::warn!(
+                "Request for {url} failed on attempt {attempt}, and the site asked to wait too long to retry: {reason}"
+            );
+            break result;
+        }
+        let delay = retry_after.unwrap_or_else(|| settings.backoff(attempt));
+        log::info!(
+            "Attempt {attempt} of {attempts} for {url} failed: {reason} - retrying in {delay:?}"
+        );
+        thread::sleep(delay);
+        attempt += 1;
+    };
+    result.map_err(|e| {
+        Scrape(format!(
+            "Request failed for {url} after {attempt} attempt(s) - check your internet connection (internal server error): {e}"
+        ))
+    })
+}
+
+fn is_retryable_status(status: u16) -> bool {
+    status == 429 || (500..=599).contains(&status)
+}
+
+fn is_retryable_error(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::Io(_) | Error::Timeout(_) | Error::ConnectionFailed
+    )
+}
+
+// Retry-After is given either in seconds, or as the date to retry at.
+fn retry_after(response: &Response<Body>) -> Option<Duration> {
+    let value = response.headers().get("Retry-After")?.to_str().ok()?.trim();
+    parse_retry_after(value, Utc::now())
+}
+
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    value
+        .parse::<u64>()
+        .map(Duration::from_secs)
+        .ok()
+        .or_else(|| {
+            DateTime::parse_from_rfc2822(value).ok().map(|date| {
+                (date.with_timezone(&Utc) - now)
+                    .to_std()
+                    .unwrap_or_default()
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_engine::test_server::serve_sequence;
+    use chrono::TimeZone;
+
+    fn no_delay(retries: u32) -> RetrySettings {
+        RetrySettings::new(retries, 1, 0)
+    }
+
+    fn status(url: &str, settings: &RetrySettings) -> Result<u16, IsError> {
+        call_with_retry(url, settings, || {
+            Ok(ureq::get(url).config().http_status_as_error(false).build())
+        })
+        .map(|response| response.status().as_u16())
+    }
+
+    #[test]
+    fn test_retries_server_errors_until_success() {
+        let url = serve_sequence(vec![
+            ("503 Service Unavailable", vec![]),
+            ("429 Too Many Requests", vec![("Retry-After", "0")]),
+            ("200 OK", vec![]),
+        ]);
+
+        assert_eq!(status(&url, &no_delay(2)).unwrap(), 200);
+    }
+
+    #[test]
+    fn test_returns_last_response_when_retries_run_out() {
+        let url = serve_sequence(vec![
+            ("500 Internal Server Error", vec![]),
+            ("502 Bad Gateway", vec![]),
+            ("200 OK", vec![]),
+        ]);
+
+        assert_eq!(status(&url, &no_delay(1)).unwrap(), 502);
+    }
+
+    #[test]
+    fn test_client_errors_are_not_retried() {
+        let url = serve_sequence(vec![("404 Not Found", vec![]), ("200 OK", vec![])]);
+
+        assert_eq!(status(&url, &no_delay(2)).unwrap(), 404);
+    }
+
+    #[test]
+    fn test_long_retry_after_is_not_waited_on() {
+        let url = serve_sequence(vec![
+            ("429 Too Many Requests", vec![("Retry-After", "3600")]),
+            ("200 OK", vec![]),
+        ]);
+
+        assert_eq!(status(&url, &no_delay(2)).unwrap(), 429);
+    }
+
+    #[test]
+    fn test_connection_errors_are_retried_then_fail() {
+        // Nothing listens on the discard port, so every attempt fails to connect.
+        let result = status("http://127.0.0.1:9", &no_delay(1));
+
+        assert!(matches!(result, Err(Scrape(message)) if message.contains("after 2 attempt(s)")));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap();
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_backoff_doubles_with_jitter() {
+        let settings = RetrySettings::new(3, 100, 50);
+        let third = settings.backoff(3);
+        assert!(third >= Duration::from_millis(400) && third <= Duration::from_millis(450));
+        assert_eq!(
+            RetrySettings::new(3, 100, 0).backoff(1),
+            Duration::from_millis(100)
+        );
+    }
+}
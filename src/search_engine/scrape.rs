@@ -1,20 +1,31 @@
 use crate::config::load::Config;
-use crate::config::site::SiteConfig;
+use crate::config::site::{CallConfig, SiteConfig};
 use crate::errors::error::IsError;
 use crate::errors::error::IsError::Scrape;
+use crate::search_engine::auth::auth_header;
 use crate::search_engine::cache::{cached_pages_purge, cached_pages_read, cached_pages_write};
-use crate::search_engine::link::HtmlSource;
+use crate::search_engine::cookies::{cookie_header, store_cookies};
+use crate::search_engine::link::HtmlSource::LinkSource;
+use crate::search_engine::link::{HtmlSource, Link};
+use crate::search_engine::proxy::proxy_for;
+use crate::search_engine::redirect::{record_final_url, redirect_target};
+use crate::search_engine::retry::call_with_retry;
+use crate::search_engine::throttle::wait_for_host;
+use crate::search_engine::tls::tls_config_for;
 use brotli::Decompressor;
+use dashmap::DashMap;
 use encoding_rs::{Encoding, UTF_8};
 use encoding_rs_io::DecodeReaderBytesBuilder;
+use flate2::read::{DeflateDecoder, MultiGzDecoder, ZlibDecoder};
 use once_cell::sync::Lazy;
+use std::collections::HashMap;
 use std::convert::Into;
 use std::io::Read;
 use std::time::Duration;
 use ureq::http::Response;
 use ureq::tls::{RootCerts, TlsConfig};
 use ureq::typestate::WithoutBody;
-use ureq::{Agent, Body};
+use ureq::{Agent, Body, ResponseExt};
 
 pub static TIMEOUT: Lazy<Duration> = Lazy::new(|| Duration::from_secs(Config::get_timeout()));
 
@@ -26,10 +37,20 @@ pub static UREQ_AGENT: Lazy<Agent> = Lazy::new(|| {
                 .build(),
         )
         .timeout_global(Some(*TIMEOUT))
+        // The proxy is chosen for each request, see get_request.
+        .proxy(None)
+        // Error statuses are returned as responses, so they can be retried and reported.
+        .http_status_as_error(false)
         .build()
         .into()
 });
 
+// The number of bytes checked for a <meta> charset declaration.
+const META_SNIFF_LENGTH: usize = 4096;
+
+// The media type of each page fetched, so pages that are not html can be shown as they are.
+static CONTENT_TYPES: Lazy<DashMap<String, String>> = Lazy::new(DashMap::new);
+
 pub static HEADER_ORDERING: Lazy<Vec<&str>> = Lazy::new(|| {
     vec![
         "User-Agent",
@@ -37,6 +58,7 @@ pub static HEADER_ORDERING: Lazy<Vec<&str>> = Lazy::new(|| {
         "Accept-Encoding",
         "Accept-Language",
         "Referer",
+        "Authorization",
         "Cookie",
         "Sec-Fetch-Site",
         "Sec-Fetch-Mode",
@@ -48,28 +70,106 @@ pub static HEADER_ORDERING: Lazy<Vec<&str>> = Lazy::new(|| {
 });
 
 pub fn scrape(html_source: &HtmlSource) -> Result<String, IsError> {
+    scrape_with_headers(html_source, &HashMap::new())
+}
+
+// Additional headers are applied on top of the site configured headers, replacing any with the same name.
+pub fn scrape_with_headers(
+    html_source: &HtmlSource,
+    additional_headers: &HashMap<String, String>,
+) -> Result<String, IsError> {
+    let mut source = html_source.clone();
+    let mut visited = vec![html_source.get_url().to_string()];
+    loop {
+        let html = fetch(&source, additional_headers)?;
+        let url = source.get_final_url();
+        let redirect = source.get_config().get_call().get_redirect().clone();
+        match redirect_target(&url, &html, &redirect) {
+            Some(target) if visited.contains(&target) => {
+                log::warn!("Not following the redirect loop from {url} to {target}");
+            }
+            Some(target) if visited.len() > redirect.get_max_hops() => {
+                log::warn!("Not following the redirect from {url} to {target}, as the hop limit was reached");
+            }
+            Some(target) => {
+                log::debug!("Following the redirect in the page from {url} to {target}");
+                visited.push(target.clone());
+                source = LinkSource(Link {
+                    cached: source.is_cached(),
+                    ..Link::new(&target)
+                });
+                continue;
+            }
+            None => {}
+        }
+        record_final_url(html_source.get_url(), &url);
+        return Ok(html);
+    }
+}
+
+fn fetch(
+    html_source: &HtmlSource,
+    additional_headers: &HashMap<String, String>,
+) -> Result<String, IsError> {
     if let Some(html) = cached_pages_read(html_source) {
         return Ok(html);
     }
-    ureq_scrape(html_source)
+    ureq_scrape(html_source, additional_headers)
         .inspect(|html| log::trace!("scraping page {html}"))
         .inspect(|html| cached_pages_write(html_source, html))
 }
 
+/// Records the media type the page was sent with, which is also restored for cached pages.
+pub fn record_content_type(url: &str, media_type: &str) {
+    CONTENT_TYPES.insert(url.to_string(), media_type.to_string());
+}
+
+/// The media type the page was sent with, such as `application/json`.
+pub fn content_type(url: &str) -> Option<String> {
+    CONTENT_TYPES.get(url).map(|media_type| media_type.clone())
+}
+
 pub fn cache_purge(url: &HtmlSource) {
     cached_pages_purge(url);
 }
 
-fn ureq_scrape(html_source: &HtmlSource) -> Result<String, IsError> {
+fn ureq_scrape(
+    html_source: &HtmlSource,
+    additional_headers: &HashMap<String, String>,
+) -> Result<String, IsError> {
     let url = html_source.get_url();
-    let mut request = UREQ_AGENT.get(url);
-    request = add_url_based_headers(&html_source.get_config(), request);
-
-    let response = request.call().map_err(|e| {
-        Scrape(format!(
-            "Request failed for {url} - check your internet connection (internal server error): {e}"
-        ))
+    let site_config = html_source.get_config();
+    let call = site_config.get_call();
+    // Held until the page is read, so preloaded pages share the connection limit for the host.
+    let _permit = wait_for_host(url, call.get_throttle());
+    let response = call_with_retry(url, call.get_retry(), || {
+        get_request(url, call).and_then(|request| {
+            add_url_based_headers(url, &site_config, additional_headers, request)
+        })
     })?;
+    // The page may have been redirected, such as from http to https, or to another host.
+    let final_url = response.get_uri().to_string();
+    record_final_url(url, &final_url);
+    // The cookies were set by the page that was finally loaded, so are checked against its host.
+    store_cookies(
+        &final_url,
+        call.get_cookies(),
+        response
+            .headers()
+            .get_all("Set-Cookie")
+            .iter()
+            .filter_map(|value| value.to_str().ok()),
+    );
+    if let Some(media_type) = response
+        .headers()
+        .get("Content-Type")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+    {
+        let media_type = media_type.trim().to_lowercase();
+        record_content_type(url, &media_type);
+        record_content_type(&final_url, &media_type);
+    }
 
     if !response.status().is_success() {
         return Err(error_for_fail_response_code(url, &response));
@@ -78,12 +178,70 @@ fn ureq_scrape(html_source: &HtmlSource) -> Result<String, IsError> {
     decode_text(url, response)
 }
 
+/// Fetches the body of an api response, throttled and retried with the settings for the site, and
+/// sent with the given headers.
+pub fn get_text(url: &str, headers: &[(&str, &str)]) -> Result<String, IsError> {
+    let call = Config::get_site_config(url).get_call();
+    let _permit = wait_for_host(url, call.get_throttle());
+    let response = call_with_retry(url, call.get_retry(), || {
+        get_request(url, call).map(|request| {
+            headers.iter().fold(request, |request, (name, value)| {
+                request.header(*name, *value)
+            })
+        })
+    })?;
+    if !response.status().is_success() {
+        return Err(Scrape(format!(
+            "Request failed for {url}: HTTP Status {}",
+            response.status()
+        )));
+    }
+    response
+        .into_body()
+        .read_to_string()
+        .map_err(|e| Scrape(format!("Failed to read response body for {url}: {e}")))
+}
+
+/// Creates a GET request for the url, sent through the proxy for the site if there is one, and
+/// using the certificates configured for the site.
+pub fn get_request(
+    url: &str,
+    call: &CallConfig,
+) -> Result<ureq::RequestBuilder<WithoutBody>, IsError> {
+    let proxy = proxy_for(url, call)?;
+    let mut config = UREQ_AGENT.get(url).config().proxy(proxy);
+    if let Some(tls_config) = tls_config_for(url, call.get_tls())? {
+        config = config.tls_config(tls_config);
+    }
+    Ok(config.build())
+}
+
 fn add_url_based_headers(
-    url: &SiteConfig,
+    url: &str,
+    site_config: &SiteConfig,
+    additional_headers: &HashMap<String, String>,
     request: ureq::RequestBuilder<WithoutBody>,
-) -> ureq::RequestBuilder<WithoutBody> {
+) -> Result<ureq::RequestBuilder<WithoutBody>, IsError> {
     let mut request = request;
-    let headers = url.get_call().get_headers();
+    let call = site_config.get_call();
+    let mut headers = call.get_headers().clone();
+    let auth = auth_header(url, call.get_auth())?.map(|auth| (String::from("Authorization"), auth));
+    for (key, value) in additional_headers.clone().into_iter().chain(auth) {
+        headers.retain(|existing, _| !existing.eq_ignore_ascii_case(&key));
+        headers.insert(key, value);
+    }
+    // Cookies from the jar are added to any Cookie header set in the config.
+    if let Some(cookies) = cookie_header(url, call.get_cookies())? {
+        let key = headers
+            .keys()
+            .find(|key| key.eq_ignore_ascii_case("Cookie"))
+            .cloned()
+            .unwrap_or_else(|| String::from("Cookie"));
+        headers
+            .entry(key)
+            .and_modify(|existing| *existing = format!("{existing}; {cookies}"))
+            .or_insert(cookies);
+    }
     let mut sorted_headers: Vec<(&String, &String)> = headers.iter().collect();
 
     sorted_headers.sort_by_key(|(key, _)| {
@@ -97,7 +255,7 @@ fn add_url_based_headers(
         request = request.header(key, value);
     }
 
-    request
+    Ok(request)
 }
 
 fn error_for_fail_response_code(url: &str, response: &Response<Body>) -> IsError {
@@ -120,11 +278,12 @@ fn decode_text(url: &str, response: Response<Body>) -> Result<String, IsError> {
         None
     };
 
-    let is_brotli = response
+    let content_encodings = response
         .headers()
         .get("Content-Encoding")
         .and_then(|e| e.to_str().ok())
-        .is_some_and(|e| e.eq_ignore_ascii_case("br"));
+        .map(content_encodings)
+        .unwrap_or_default();
 
     let mut bytes = Vec::new();
     let mut reader = response.into_body().into_reader();
@@ -134,31 +293,304 @@ fn decode_text(url: &str, response: Response<Body>) -> Result<String, IsError> {
         ))
     })?;
 
-    if is_brotli {
-        let mut decompressed = Vec::new();
-        let mut brotli_decoder = Decompressor::new(&*bytes, 4096);
-        brotli_decoder.read_to_end(&mut decompressed).map_err(|_| {
+    // Encodings are listed in the order they were applied, so are removed from the last.
+    for encoding in content_encodings.iter().rev() {
+        bytes = decompress(&bytes, encoding).map_err(|e| {
             Scrape(format!(
-                "Request failed for {url}, Brotli decompression failed."
+                "Request failed for {url}, {encoding} decompression failed: {e}"
             ))
         })?;
-        bytes = decompressed;
     }
 
-    let encoding = encoding_from_headers
-        .or_else(|| Encoding::for_bom(&bytes).map(|(enc, _)| enc))
+    decode_html(&bytes, encoding_from_headers).map_err(|_| {
+        Scrape(format!(
+            "Request failed for {url}, could not decode content."
+        ))
+    })
+}
+
+/// Decodes the html with the declared encoding if there is one, otherwise the encoding from the
+/// byte order mark or a `<meta>` charset declaration, falling back to UTF-8.
+pub fn decode_html(
+    bytes: &[u8],
+    declared_encoding: Option<&'static Encoding>,
+) -> std::io::Result<String> {
+    let encoding = declared_encoding
+        .or_else(|| Encoding::for_bom(bytes).map(|(enc, _)| enc))
+        .or_else(|| meta_charset(bytes))
         .unwrap_or(UTF_8);
 
     let mut decoder = DecodeReaderBytesBuilder::new()
         .encoding(Some(encoding))
-        .build(&*bytes);
+        .build(bytes);
 
     let mut text = String::new();
-    decoder.read_to_string(&mut text).map_err(|_| {
-        Scrape(format!(
-            "Request failed for {url}, could not decode content."
-        ))
-    })?;
-
+    decoder.read_to_string(&mut text)?;
     Ok(text)
 }
+
+// Like browsers, only the start of the document is checked. This covers both <meta charset="x">
+// and <meta http-equiv="Content-Type" content="text/html; charset=x">. The declaration is plain
+// ASCII in every encoding that can declare itself this way, so it can be read from the raw bytes.
+fn meta_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+    let start =
+        String::from_utf8_lossy(&bytes[..bytes.len().min(META_SNIFF_LENGTH)]).to_ascii_lowercase();
+    start
+        .split("<meta")
+        .skip(1)
+        .filter_map(|tag| {
+            let tag = &tag[..tag.find('>').unwrap_or(tag.len())];
+            let value = tag[tag.find("charset")? + 7..]
+                .trim_start()
+                .strip_prefix('=')?
+                .trim_start()
+                .trim_start_matches(['"', '\'']);
+            let end = value
+                .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | ';' | '/'))
+                .unwrap_or(value.len());
+            Encoding::for_label(&value.as_bytes()[..end])
+        })
+        // A page that has been read as ASCII cannot be UTF-16, so it is read as UTF-8 instead.
+        .map(Encoding::output_encoding)
+        .next()
+}
+
+// ureq already decompresses the body when the encoding is exactly gzip, so there is nothing left
+// to do for it. Any other value, including gzip stacked with other encodings, is left for us.
+fn content_encodings(header: &str) -> Vec<String> {
+    if header == "gzip" {
+        return vec![];
+    }
+    header
+        .split(',')
+        .map(|encoding| encoding.trim().to_lowercase())
+        .filter(|encoding| !encoding.is_empty() && encoding != "identity")
+        .collect()
+}
+
+fn decompress(bytes: &[u8], encoding: &str) -> Result<Vec<u8>, String> {
+    let mut decompressed = Vec::new();
+    let result = match encoding {
+        "br" => Decompressor::new(bytes, 4096).read_to_end(&mut decompressed),
+        "gzip" | "x-gzip" => MultiGzDecoder::new(bytes).read_to_end(&mut decompressed),
+        // Deflate should be zlib wrapped, but some servers send the raw deflate stream instead.
+        "deflate" => ZlibDecoder::new(bytes)
+            .read_to_end(&mut decompressed)
+            .or_else(|_| {
+                decompressed.clear();
+                DeflateDecoder::new(bytes).read_to_end(&mut decompressed)
+            }),
+        "zstd" => zstd::stream::copy_decode(bytes, &mut decompressed).map(|_| decompressed.len()),
+        unknown => return Err(format!("unsupported content encoding {unknown}")),
+    };
+    result.map(|_| decompressed).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::site::CallConfig;
+    use crate::search_engine::cookies::{cookie_header, CookieSettings};
+    use crate::search_engine::link::tests::TEST_CONFIG;
+    use crate::search_engine::test_server::{serve_once, serve_sequence, serve_with_headers};
+    use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+    use flate2::Compression;
+    use serial_test::serial;
+    use std::env;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    const HTML: &str = "<html><body><p>Decoded content</p></body></html>";
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn zlib(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn raw_deflate(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn brotli(bytes: &[u8]) -> Vec<u8> {
+        let mut compressed = Vec::new();
+        {
+            let mut encoder = brotli::CompressorWriter::new(&mut compressed, 4096, 5, 22);
+            encoder.write_all(bytes).unwrap();
+        }
+        compressed
+    }
+
+    fn zstd(bytes: &[u8]) -> Vec<u8> {
+        zstd::encode_all(bytes, 0).unwrap()
+    }
+
+    fn fetch(content_encoding: &str, body: &[u8]) -> Result<String, IsError> {
+        let url = serve_with_headers(
+            "200 OK",
+            &[
+                ("Content-Type", "text/html; charset=utf-8"),
+                ("Content-Encoding", content_encoding),
+            ],
+            body,
+        );
+        let response = UREQ_AGENT.get(&url).call().unwrap();
+        decode_text(&url, response)
+    }
+
+    #[test]
+    fn test_decode_single_encodings() {
+        let html = HTML.as_bytes();
+        assert_eq!(fetch("gzip", &gzip(html)).unwrap(), HTML);
+        assert_eq!(fetch("x-gzip", &gzip(html)).unwrap(), HTML);
+        assert_eq!(fetch("deflate", &zlib(html)).unwrap(), HTML);
+        assert_eq!(fetch("deflate", &raw_deflate(html)).unwrap(), HTML);
+        assert_eq!(fetch("br", &brotli(html)).unwrap(), HTML);
+        assert_eq!(fetch("zstd", &zstd(html)).unwrap(), HTML);
+        assert_eq!(fetch("identity", html).unwrap(), HTML);
+    }
+
+    #[test]
+    fn test_decode_stacked_encodings() {
+        let html = HTML.as_bytes();
+        assert_eq!(fetch("gzip, br", &brotli(&gzip(html))).unwrap(), HTML);
+        assert_eq!(fetch("zstd,gzip", &gzip(&zstd(html))).unwrap(), HTML);
+        assert_eq!(fetch("Deflate, ZSTD", &zstd(&zlib(html))).unwrap(), HTML);
+    }
+
+    #[test]
+    fn test_meta_refresh_is_followed_to_final_page() {
+        let page = serve_once("200 OK", "text/html", HTML.as_bytes());
+        let stub = format!(r#"<meta http-equiv="refresh" content="0; url={page}/article">"#);
+        let stub_url = serve_once("200 OK", "text/html", stub.as_bytes());
+        let source = LinkSource(Link::new(&stub_url));
+
+        assert_eq!(scrape(&source).unwrap(), HTML);
+        assert_eq!(source.get_final_url(), format!("{page}/article"));
+    }
+
+    #[test]
+    fn test_http_redirect_records_final_url() {
+        let page = serve_once("200 OK", "text/html", HTML.as_bytes());
+        let moved = serve_with_headers(
+            "301 Moved Permanently",
+            &[("Location", &format!("{page}/docs"))],
+            b"",
+        );
+        let source = LinkSource(Link::new(&moved));
+
+        assert_eq!(scrape(&source).unwrap(), HTML);
+        assert_eq!(source.get_final_url(), format!("{page}/docs"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_cookies_are_stored_for_redirected_host() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        env::set_var("XDG_DATA_HOME", temp_dir.path());
+        let cookies = CookieSettings::new(true, None);
+        TEST_CONFIG.write().call.cookies = cookies.clone();
+        let page = serve_with_headers(
+            "200 OK",
+            &[("Content-Type", "text/html"), ("Set-Cookie", "session=1")],
+            HTML.as_bytes(),
+        )
+        .replace("127.0.0.1", "localhost");
+        let moved = serve_with_headers(
+            "301 Moved Permanently",
+            &[("Location", &format!("{page}/docs"))],
+            b"",
+        );
+
+        let page_html = scrape(&LinkSource(Link::new(&moved)));
+        TEST_CONFIG.write().call = CallConfig::default();
+
+        assert_eq!(page_html.unwrap(), HTML);
+        assert_eq!(
+            cookie_header(&format!("{page}/docs"), &cookies).unwrap(),
+            Some(String::from("session=1"))
+        );
+        assert_eq!(cookie_header(&moved, &cookies).unwrap(), None);
+    }
+
+    #[test]
+    fn test_get_text_retries_and_checks_status() {
+        let url = serve_sequence(vec![
+            ("429 Too Many Requests", vec![("Retry-After", "0")]),
+            ("200 OK", vec![("Content-Type", "application/json")]),
+        ]);
+        assert_eq!(
+            get_text(&url, &[("Accept", "application/json")]).unwrap(),
+            ""
+        );
+
+        let url = serve_once("404 Not Found", "application/json", b"{}");
+        assert!(matches!(get_text(&url, &[]), Err(Scrape(_))));
+    }
+
+    #[test]
+    fn test_content_type_is_recorded() {
+        let url = serve_once("200 OK", "Application/JSON; charset=utf-8", b"{}");
+        let source = LinkSource(Link::new(&url));
+
+        assert_eq!(content_type(&url), None);
+        assert_eq!(scrape(&source).unwrap(), "{}");
+        assert_eq!(content_type(&url), Some(String::from("application/json")));
+    }
+
+    #[test]
+    fn test_decode_uses_meta_charset() {
+        let (html, _, _) = encoding_rs::WINDOWS_1251
+            .encode("<html><head><meta charset=\"windows-1251\"></head><p>Привет</p></html>");
+        let url = serve_once("200 OK", "text/html", &html);
+        let response = UREQ_AGENT.get(&url).call().unwrap();
+
+        let text = decode_text(&url, response).unwrap();
+
+        assert!(text.contains("<p>Привет</p>"));
+    }
+
+    #[test]
+    fn test_header_charset_overrides_meta_charset() {
+        let html = "<meta charset='shift_jis'><p>Café</p>";
+        assert_eq!(
+            decode_html(html.as_bytes(), Some(UTF_8)).unwrap(),
+            "<meta charset='shift_jis'><p>Café</p>"
+        );
+    }
+
+    #[test]
+    fn test_meta_charset() {
+        assert_eq!(
+            meta_charset(b"<html><head><META charset = 'Shift_JIS' />"),
+            Some(encoding_rs::SHIFT_JIS)
+        );
+        assert_eq!(
+            meta_charset(
+                b"<meta name=viewport><meta http-equiv=\"Content-Type\" content=\"text/html; charset=iso-8859-1\">"
+            ),
+            Some(encoding_rs::WINDOWS_1252)
+        );
+        assert_eq!(meta_charset(b"<meta charset=utf-16>"), Some(UTF_8));
+        assert_eq!(meta_charset(b"<meta name=\"description\">"), None);
+        assert_eq!(meta_charset(b"<p>charset=shift_jis</p>"), None);
+    }
+
+    #[test]
+    fn test_decode_unsupported_encoding_errors() {
+        assert!(matches!(fetch("compress", b"data"), Err(Scrape(_))));
+    }
+
+    #[test]
+    fn test_decode_corrupt_content_errors() {
+        assert!(matches!(fetch("zstd", HTML.as_bytes()), Err(Scrape(_))));
+    }
+}
@@ -1,32 +1,261 @@
 use crate::config::load::Config;
 use crate::errors::error::IsError;
+use crate::search_engine::domain_filter::DomainFilter;
 use crate::search_engine::link::Link;
 use crate::search_engine::search_type::{Search, SearchEngine};
 use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::thread;
 use std::thread::sleep;
 use std::time::Duration;
 
-static SEARCH_ENGINE: Lazy<&SearchEngine> = Lazy::new(Config::get_search_engine);
-
-pub fn find_links(search_term: &str) -> Result<Vec<Link>, IsError> {
-    let mut last_error = None;
-    std::iter::repeat_with(|| SEARCH_ENGINE.search(search_term))
-        .take(2) // Max time = 8s (2*2 s for reqwest, 2*2s for curl)
-        .find_map(|result| match result {
-            Ok(links) if !links.is_empty() => Some(links),
-            Err(e) => {
-                log::debug!("failed to search links: {e:?}");
-                last_error = Some(e.to_string());
-                sleep(Duration::from_secs(1)); // Wait before retrying
-                None
-            }
-            _ => {
-                log::debug!("failed to search links: no links found");
-                sleep(Duration::from_secs(1)); // Wait before retrying
-                None
-            } // Empty links, try again
+static SEARCH_ENGINES: Lazy<&Result<Vec<SearchEngine>, IsError>> =
+    Lazy::new(Config::get_search_engines);
+static DOMAIN_FILTER: Lazy<&DomainFilter> = Lazy::new(Config::get_domain_filter);
+static FALLBACK_ENGINES: Lazy<&[SearchEngine]> = Lazy::new(Config::get_fallback_engines);
+
+pub fn find_links(search_term: &str, page: usize) -> Result<Vec<Link>, IsError> {
+    let engines = SEARCH_ENGINES.as_ref().map_err(|e| match e {
+        IsError::Search(message) => IsError::Search(message.clone()),
+        e => IsError::General(e.to_string()),
+    })?;
+    let links = search_with_fallback(engines, &FALLBACK_ENGINES, search_term, page)?;
+    let links = DOMAIN_FILTER.filter(links);
+    if links.is_empty() {
+        return Err(IsError::Search(String::from(
+            "All results were removed by the site restrictions",
+        )));
+    }
+    Ok(links)
+}
+
+// The fallback engines are only tried in order when everything before them has failed, e.g. when
+// rate limited or out of quota.
+fn search_with_fallback(
+    engines: &[SearchEngine],
+    fallback_engines: &[SearchEngine],
+    search_term: &str,
+    page: usize,
+) -> Result<Vec<Link>, IsError> {
+    fallback_engines.iter().fold(
+        search_engines(engines, search_term, page),
+        |result, fallback| {
+            result.or_else(|e| {
+                log::warn!("Search failed, falling back to {}: {e}", fallback.name());
+                search_engine(fallback, search_term, page)
+                    .map(|links| mark_fallback(links, fallback))
+            })
+        },
+    )
+}
+
+// The engine is shown with the page, so marking it lets the user know the configured engine failed.
+fn mark_fallback(links: Vec<Link>, fallback: &SearchEngine) -> Vec<Link> {
+    links
+        .into_iter()
+        .map(|mut link| {
+            link.engine = Some(format!(
+                "{} (fallback)",
+                link.engine.as_deref().unwrap_or(fallback.name())
+            ));
+            link
         })
-        .ok_or(IsError::Search(last_error.unwrap_or_else(|| {
-            String::from("No links were found, no error detected")
-        })))
+        .collect()
+}
+
+fn search_engines(
+    engines: &[SearchEngine],
+    search_term: &str,
+    page: usize,
+) -> Result<Vec<Link>, IsError> {
+    if let [engine] = engines {
+        return search_engine(engine, search_term, page);
+    }
+    // Every engine is queried at once, so the total wait is that of the slowest engine.
+    let results = thread::scope(|scope| {
+        engines
+            .iter()
+            .map(|engine| scope.spawn(move || search_engine(engine, search_term, page)))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| {
+                handle.join().unwrap_or_else(|_| {
+                    Err(IsError::Search(String::from("Search thread panicked")))
+                })
+            })
+            .collect::<Vec<Result<Vec<Link>, IsError>>>()
+    });
+    let mut errors = Vec::new();
+    let link_lists = results
+        .into_iter()
+        .filter_map(|result| result.map_err(|e| errors.push(e.to_string())).ok())
+        .collect::<Vec<Vec<Link>>>();
+    if link_lists.is_empty() {
+        return Err(IsError::Search(errors.join("; ")));
+    }
+    Ok(merge_links(link_lists))
+}
+
+// Requests are already retried when they fail, so errors are returned straight away for the
+// fallback engines to be tried. An empty result is searched for once more after a short wait.
+fn search_engine(
+    engine: &SearchEngine,
+    search_term: &str,
+    page: usize,
+) -> Result<Vec<Link>, IsError> {
+    for attempt in 1..=2 {
+        let links = engine.search(search_term, page).inspect_err(|e| {
+            log::debug!("failed to search links: {e:?}");
+        })?;
+        if !links.is_empty() {
+            return Ok(links);
+        }
+        log::debug!("failed to search links: no links found on attempt {attempt}");
+        if attempt == 1 {
+            sleep(Duration::from_secs(1));
+        }
+    }
+    Err(IsError::Search(String::from(
+        "No links were found, no error detected",
+    )))
+}
+
+// Results are interleaved so that the top results of every engine come first. When more than one
+// engine finds the same page the first result is kept, and the engine names are combined.
+fn merge_links(link_lists: Vec<Vec<Link>>) -> Vec<Link> {
+    let longest = link_lists.iter().map(Vec::len).max().unwrap_or_default();
+    let mut iterators = link_lists
+        .into_iter()
+        .map(Vec::into_iter)
+        .collect::<Vec<_>>();
+    let mut merged: Vec<Link> = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for _ in 0..longest {
+        for link in iterators.iter_mut().filter_map(Iterator::next) {
+            match seen.get(&normalize_url(&link.url)) {
+                Some(&position) => add_engine(&mut merged[position], &link),
+                None => {
+                    seen.insert(normalize_url(&link.url), merged.len());
+                    merged.push(link);
+                }
+            }
+        }
+    }
+    merged
+}
+
+fn add_engine(existing: &mut Link, duplicate: &Link) {
+    let Some(engine) = &duplicate.engine else {
+        return;
+    };
+    match &existing.engine {
+        Some(engines) if engines.split(", ").any(|e| e == engine) => {}
+        Some(engines) => existing.engine = Some(format!("{engines}, {engine}")),
+        None => existing.engine = Some(engine.clone()),
+    }
+    if existing.snippet.is_none() {
+        existing.snippet.clone_from(&duplicate.snippet);
+    }
+    if existing.title.is_none() {
+        existing.title.clone_from(&duplicate.title);
+    }
+}
+
+// Only the scheme and host are case insensitive, paths and queries can differ by case.
+pub(crate) fn normalize_url(url: &str) -> String {
+    let url = url.trim().split('#').next().unwrap_or_default();
+    let url = match url.split_once("://") {
+        Some((scheme, rest)) if matches!(scheme.to_lowercase().as_str(), "http" | "https") => rest,
+        Some(_) | None => url,
+    };
+    let host_end = url.find(['/', '?']).unwrap_or(url.len());
+    let (host, path) = url.split_at(host_end);
+    let host = host.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    format!("{host}{}", path.trim_end_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_engine::searxng::SearxngSearch;
+    use crate::search_engine::test_server::serve_once;
+
+    #[test]
+    fn test_normalize_url_keeps_path_case() {
+        assert_eq!(
+            normalize_url("HTTPS://WWW.Example.COM/Wiki/Rust?Page=Two"),
+            "example.com/Wiki/Rust?Page=Two"
+        );
+        assert_ne!(
+            normalize_url("https://example.com/Page"),
+            normalize_url("https://example.com/page")
+        );
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize_url("https://www.Rust-Lang.org/learn/#start"),
+            "rust-lang.org/learn"
+        );
+        assert_eq!(
+            normalize_url("http://rust-lang.org/learn"),
+            "rust-lang.org/learn"
+        );
+    }
+
+    #[test]
+    fn test_merge_links_interleaves_and_deduplicates() {
+        let ddg = vec![
+            Link::from_search("https://www.rust-lang.org/", "Rust", "", "duckduckgo"),
+            Link::from_search("https://doc.rust-lang.org/book/", "Book", "", "duckduckgo"),
+            Link::from_search("https://crates.io", "Crates", "", "duckduckgo"),
+        ];
+        let searxng = vec![
+            Link::from_search("https://docs.rs", "Docs", "", "searxng"),
+            Link::from_search("http://rust-lang.org", "Rust", "A language", "searxng"),
+        ];
+
+        let merged = merge_links(vec![ddg, searxng]);
+
+        let urls = merged.iter().map(|l| l.url.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            urls,
+            vec![
+                "https://www.rust-lang.org/",
+                "https://docs.rs",
+                "https://doc.rust-lang.org/book/",
+                "https://crates.io",
+            ]
+        );
+        assert_eq!(merged[0].engine.as_deref(), Some("duckduckgo, searxng"));
+        assert_eq!(merged[0].snippet.as_deref(), Some("A language"));
+        assert_eq!(merged[1].engine.as_deref(), Some("searxng"));
+    }
+
+    #[test]
+    fn test_search_with_fallback_uses_next_engine() {
+        let body = r#"{"results": [{"url": "https://www.rust-lang.org/", "title": "Rust"}]}"#;
+        let base_url = serve_once("200 OK", "application/json", body.as_bytes());
+        let failing = SearchEngine::Searxng(SearxngSearch::new(""));
+        let fallback = SearchEngine::Searxng(SearxngSearch::new(&base_url));
+
+        let links = search_with_fallback(&[failing], &[fallback], "rust", 0).unwrap();
+
+        assert_eq!(links[0].url, "https://www.rust-lang.org/");
+        assert_eq!(links[0].engine.as_deref(), Some("searxng (fallback)"));
+    }
+
+    #[test]
+    fn test_search_with_fallback_keeps_working_engine() {
+        let body = r#"{"results": [{"url": "https://www.rust-lang.org/", "title": "Rust"}]}"#;
+        let base_url = serve_once("200 OK", "application/json", body.as_bytes());
+        let working = SearchEngine::Searxng(SearxngSearch::new(&base_url));
+        let fallback = SearchEngine::Searxng(SearxngSearch::new(""));
+
+        let links = search_with_fallback(&[working], &[fallback], "rust", 0).unwrap();
+
+        assert_eq!(links[0].engine.as_deref(), Some("searxng"));
+    }
 }
@@ -3,24 +3,51 @@ use crate::search_engine::duckduckgo::DuckDuckGoSearch;
 use crate::search_engine::google::GoogleSearch;
 use crate::search_engine::kagi::KagiSearch;
 use crate::search_engine::link::Link;
+use crate::search_engine::local::LocalSearch;
+use crate::search_engine::searxng::SearxngSearch;
+use crate::search_engine::template::TemplateSearch;
+use crate::search_engine::wikipedia::WikipediaSearch;
+use crate::search_engine::{duckduckgo, google, kagi, local, searxng, wikipedia};
 
 #[derive(Debug, Clone)]
 pub enum SearchEngine {
     DuckDuckGo,
     Google,
     Kagi,
+    Local,
+    Searxng(SearxngSearch),
+    Template(TemplateSearch),
+    Wikipedia(WikipediaSearch),
+}
+
+impl SearchEngine {
+    pub fn name(&self) -> &str {
+        match self {
+            SearchEngine::DuckDuckGo => duckduckgo::ENGINE_NAME,
+            SearchEngine::Google => google::ENGINE_NAME,
+            SearchEngine::Kagi => kagi::ENGINE_NAME,
+            SearchEngine::Local => local::ENGINE_NAME,
+            SearchEngine::Searxng(_) => searxng::ENGINE_NAME,
+            SearchEngine::Template(template) => template.name(),
+            SearchEngine::Wikipedia(_) => wikipedia::ENGINE_NAME,
+        }
+    }
 }
 
 /// # Adding a New Search Engine
 ///
-/// To add a new search engine, follow these steps:
+/// Sites with a plain HTML results page can be added without any code by defining a
+/// `[search.engines.<name>]` section in the configuration, with a url template and a CSS selector
+/// for the result links. These are backed by [`TemplateSearch`].
+///
+/// For anything more involved, follow these steps:
 ///
 /// 1. Implement the [`Search`] trait for your new engine:
 ///    ```rust
 ///    struct MySearchEngine;
 ///
 ///    impl Search for MySearchEngine {
-///        fn search(query: &str) -> Vec<Link> {
+///        fn search(query: &str, page: usize) -> Vec<Link> {
 ///            // Custom search logic
 ///            vec![]
 ///        }
@@ -47,15 +74,21 @@ pub enum SearchEngine {
 /// - [`SearchEngine`] for the available engines.
 /// - [`Search`] trait for implementing a new search.
 impl Search for SearchEngine {
-    fn search(&self, query: &str) -> Result<Vec<Link>, IsError> {
+    fn search(&self, query: &str, page: usize) -> Result<Vec<Link>, IsError> {
         match self {
-            SearchEngine::DuckDuckGo => DuckDuckGoSearch.search(query),
-            SearchEngine::Google => GoogleSearch.search(query),
-            SearchEngine::Kagi => KagiSearch.search(query),
+            SearchEngine::DuckDuckGo => DuckDuckGoSearch.search(query, page),
+            SearchEngine::Google => GoogleSearch.search(query, page),
+            SearchEngine::Kagi => KagiSearch.search(query, page),
+            SearchEngine::Local => LocalSearch.search(query, page),
+            SearchEngine::Searxng(searxng) => searxng.search(query, page),
+            SearchEngine::Template(template) => template.search(query, page),
+            SearchEngine::Wikipedia(wikipedia) => wikipedia.search(query, page),
         }
     }
 }
 
 pub trait Search {
-    fn search(&self, query: &str) -> Result<Vec<Link>, IsError>;
+    /// Searches for the given query. The page starts at 0 for the first page of results, and is
+    /// increased to load more results.
+    fn search(&self, query: &str, page: usize) -> Result<Vec<Link>, IsError>;
 }
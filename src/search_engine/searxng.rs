@@ -0,0 +1,166 @@
+use crate::cli::command::SafeSearch;
+use crate::config::load::Config;
+use crate::errors::error::IsError;
+use crate::errors::error::IsError::Search as SearchError;
+use crate::search_engine::link::Link;
+use crate::search_engine::locale::SearchLocale;
+use crate::search_engine::scrape::get_text;
+use crate::search_engine::search_type::Search;
+use once_cell::sync::Lazy;
+use serde_json::from_str;
+
+#[derive(serde::Deserialize)]
+struct SearchResult {
+    results: Vec<SearchItem>,
+}
+
+#[derive(serde::Deserialize)]
+struct SearchItem {
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    content: String,
+}
+
+pub(crate) const ENGINE_NAME: &str = "searxng";
+
+static LOCALE: Lazy<&SearchLocale> = Lazy::new(Config::get_search_locale);
+
+#[derive(Debug, Clone)]
+pub struct SearxngSearch {
+    base_url: String,
+}
+
+impl SearxngSearch {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn search_result_to_links(search_result: &SearchResult) -> Vec<Link> {
+        search_result
+            .results
+            .iter()
+            .filter(|item| !item.url.is_empty())
+            .map(|item| Link::from_search(&item.url, &item.title, &item.content, ENGINE_NAME))
+            .collect()
+    }
+
+    // The instance must have the json format enabled under `search.formats` in its settings.yml.
+    fn search_url(&self, query: &str, page: usize, locale: &SearchLocale) -> String {
+        let mut url = format!(
+            "{}/search?q={query}&format=json&pageno={}",
+            self.base_url,
+            page + 1
+        );
+        // SearXNG takes the region as part of the language, e.g. en-GB.
+        if let Some(language) = &locale.language {
+            match &locale.region {
+                Some(region) => {
+                    url.push_str(&format!("&language={language}-{}", region.to_uppercase()))
+                }
+                None => url.push_str(&format!("&language={language}")),
+            }
+        }
+        if let Some(safe_search) = &locale.safe_search {
+            let level = match safe_search {
+                SafeSearch::Off => 0,
+                SafeSearch::Moderate => 1,
+                SafeSearch::Strict => 2,
+            };
+            url.push_str(&format!("&safesearch={level}"));
+        }
+        url
+    }
+
+    fn request_results(&self, query: &str, page: usize) -> Result<String, IsError> {
+        get_text(
+            &self.search_url(query, page, &LOCALE),
+            &[("Accept", "application/json")],
+        )
+    }
+
+    fn get_links(&self, query: &str, page: usize) -> Result<Vec<Link>, IsError> {
+        self.request_results(query, page)
+            .and_then(|json| {
+                from_str::<SearchResult>(&json).map_err(|e| SearchError(e.to_string()))
+            })
+            .map(|search_result| Self::search_result_to_links(&search_result))
+            .map_err(|e| SearchError(e.to_string()))
+    }
+}
+
+impl Search for SearxngSearch {
+    fn search(&self, query: &str, page: usize) -> Result<Vec<Link>, IsError> {
+        if self.base_url.is_empty() {
+            return Err(SearchError(String::from(
+                "No SearXNG instance configured - set searxng_url in the [search] section",
+            )));
+        }
+        self.get_links(query, page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_engine::test_server::serve_once;
+
+    #[test]
+    fn test_searxng_links_from_json() {
+        let body = r#"{"query": "rust", "results": [
+            {"url": "https://www.rust-lang.org/", "title": "Rust", "content": "A language"},
+            {"title": "No url"},
+            {"url": "https://doc.rust-lang.org/book/", "title": "The Book", "content": ""}
+        ]}"#;
+        let base_url = serve_once("200 OK", "application/json", body.as_bytes());
+
+        let links = SearxngSearch::new(&base_url).search("rust", 0).unwrap();
+
+        let urls = links.iter().map(|l| l.url.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            urls,
+            vec![
+                "https://www.rust-lang.org/",
+                "https://doc.rust-lang.org/book/"
+            ]
+        );
+        assert_eq!(links[0].title.as_deref(), Some("Rust"));
+        assert_eq!(links[0].snippet.as_deref(), Some("A language"));
+        assert_eq!(links[0].engine.as_deref(), Some("searxng"));
+        assert_eq!(links[1].snippet, None);
+    }
+
+    #[test]
+    fn test_searxng_search_url_with_locale() {
+        let locale = SearchLocale::new(
+            Some(String::from("gb")),
+            Some(String::from("en")),
+            Some(SafeSearch::Off),
+        );
+        assert_eq!(
+            SearxngSearch::new("http://localhost:8888/").search_url("rust", 0, &locale),
+            "http://localhost:8888/search?q=rust&format=json&pageno=1&language=en-GB&safesearch=0"
+        );
+    }
+
+    #[test]
+    fn test_searxng_error_status_is_search_error() {
+        let base_url = serve_once("403 Forbidden", "text/html", b"format not allowed");
+
+        let result = SearxngSearch::new(&base_url).search("rust", 0);
+
+        assert!(matches!(result, Err(SearchError(_))));
+    }
+
+    #[test]
+    fn test_searxng_without_instance_errors() {
+        assert!(matches!(
+            SearxngSearch::new("").search("rust", 0),
+            Err(SearchError(_))
+        ));
+    }
+}
@@ -0,0 +1,223 @@
+use crate::errors::error::IsError;
+use crate::errors::error::IsError::{Search as SearchError, Selector as SelectorError};
+use crate::search_engine::link::HtmlSource::LinkSource;
+use crate::search_engine::link::Link;
+use crate::search_engine::redirect::{final_url, resolve_url};
+use crate::search_engine::scrape::{cache_purge, scrape_with_headers};
+use crate::search_engine::search_type::Search;
+use scraper::{Html, Selector};
+use std::collections::HashMap;
+
+const QUERY_PLACEHOLDER: &str = "{query}";
+const PAGE_PLACEHOLDER: &str = "{page}";
+
+/// A search engine defined in the user configuration. The results page is fetched from the url
+/// template and the result links are extracted with the given CSS selector.
+#[derive(Debug, Clone)]
+pub struct TemplateSearch {
+    name: String,
+    url: String,
+    selector: String,
+    attribute: String,
+    headers: HashMap<String, String>,
+}
+
+impl TemplateSearch {
+    pub fn new(
+        name: String,
+        url: String,
+        selector: String,
+        attribute: Option<String>,
+        headers: HashMap<String, String>,
+    ) -> Self {
+        Self {
+            name,
+            url,
+            selector,
+            attribute: attribute.unwrap_or_else(|| String::from("href")),
+            headers,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Pages are numbered from 1 in the url, as most sites do.
+    fn search_url(&self, query: &str, page: usize) -> String {
+        let url = self.url.replace(PAGE_PLACEHOLDER, &(page + 1).to_string());
+        if url.contains(QUERY_PLACEHOLDER) {
+            url.replace(QUERY_PLACEHOLDER, query)
+        } else {
+            format!("{url}{query}")
+        }
+    }
+
+    // Result pages frequently link relative to the page, so the links are resolved against the
+    // url the results were loaded from. Links given as text are shown as written, so these are used as they are.
+    fn links_from_html(&self, html: &str, search_url: &str) -> Result<Vec<Link>, IsError> {
+        let selector = Selector::parse(&self.selector).map_err(|_| {
+            SelectorError(format!(
+                "Failed to create result selector {}",
+                self.selector
+            ))
+        })?;
+        let links = Html::parse_document(html)
+            .select(&selector)
+            .map(|element| {
+                let text = element.text().collect::<String>();
+                match element.value().attr(&self.attribute) {
+                    Some(link) => (link.trim().to_string(), text, true),
+                    None => (text.trim().to_string(), String::new(), false),
+                }
+            })
+            .filter(|(link, _, _)| !link.is_empty() && !link.starts_with('#'))
+            .map(|(link, title, from_attribute)| {
+                let url = if from_attribute {
+                    resolve_url(search_url, &link)
+                } else {
+                    link
+                };
+                Link::from_search(&url, &title, "", &self.name)
+            })
+            .collect::<Vec<Link>>();
+        if links.is_empty() {
+            Err(SearchError(String::from("No links found")))
+        } else {
+            Ok(links)
+        }
+    }
+}
+
+impl Search for TemplateSearch {
+    fn search(&self, query: &str, page: usize) -> Result<Vec<Link>, IsError> {
+        if page > 0 && !self.url.contains(PAGE_PLACEHOLDER) {
+            return Err(SearchError(format!(
+                "The {} engine has no {PAGE_PLACEHOLDER} in its url, so more results cannot be loaded",
+                self.name
+            )));
+        }
+        let search_url = self.search_url(query, page);
+        let html_source = &LinkSource(Link::new(&search_url));
+        scrape_with_headers(html_source, &self.headers).and_then(|html| {
+            self.links_from_html(&html, &final_url(&search_url))
+                .inspect_err(|_| cache_purge(html_source))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MDN_SEARCH: &str = "https://developer.mozilla.org/en-US/search?q=array";
+
+    fn mdn() -> TemplateSearch {
+        TemplateSearch::new(
+            String::from("mdn"),
+            String::from("https://developer.mozilla.org/en-US/search?q={query}"),
+            String::from("li.result a"),
+            None,
+            HashMap::new(),
+        )
+    }
+
+    #[test]
+    fn test_search_url_replaces_placeholder() {
+        assert_eq!(
+            mdn().search_url("array+map", 0),
+            "https://developer.mozilla.org/en-US/search?q=array+map"
+        );
+    }
+
+    #[test]
+    fn test_search_url_appends_without_placeholder() {
+        let search = TemplateSearch::new(
+            String::from("crates"),
+            String::from("https://crates.io/search?q="),
+            String::from("a"),
+            None,
+            HashMap::new(),
+        );
+        assert_eq!(
+            search.search_url("serde", 0),
+            "https://crates.io/search?q=serde"
+        );
+    }
+
+    #[test]
+    fn test_search_url_replaces_page() {
+        let search = TemplateSearch::new(
+            String::from("crates"),
+            String::from("https://crates.io/search?q={query}&page={page}"),
+            String::from("a"),
+            None,
+            HashMap::new(),
+        );
+        assert_eq!(
+            search.search_url("serde", 1),
+            "https://crates.io/search?q=serde&page=2"
+        );
+    }
+
+    #[test]
+    fn test_search_without_page_placeholder_cannot_page() {
+        assert!(matches!(mdn().search("rust", 1), Err(SearchError(_))));
+    }
+
+    #[test]
+    fn test_links_from_html_resolves_relative_links() {
+        let html = r##"<html><body><ul>
+            <li class="result"><a href="/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/map">map</a></li>
+            <li class="result"><a href="//example.com/page">external</a></li>
+            <li class="result"><a href="https://other.org/doc">absolute</a></li>
+            <li class="result"><a href="docs/Web/API">relative</a></li>
+            <li class="result"><a href="?q=array&amp;page=2">query</a></li>
+            <li class="result"><a href="#top">anchor</a></li>
+            <li class="other"><a href="/ignored">ignored</a></li>
+        </ul></body></html>"##;
+
+        let links = mdn().links_from_html(html, MDN_SEARCH).unwrap();
+
+        let urls = links.iter().map(|l| l.url.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            urls,
+            vec![
+                "https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/map",
+                "https://example.com/page",
+                "https://other.org/doc",
+                "https://developer.mozilla.org/en-US/docs/Web/API",
+                "https://developer.mozilla.org/en-US/search?q=array&page=2",
+            ]
+        );
+        assert_eq!(links[0].title.as_deref(), Some("map"));
+        assert_eq!(links[0].engine.as_deref(), Some("mdn"));
+    }
+
+    #[test]
+    fn test_links_from_html_uses_text_without_attribute() {
+        let search = TemplateSearch::new(
+            String::from("ddg"),
+            String::from("https://html.duckduckgo.com/html/?q={query}"),
+            String::from("a.result__url"),
+            Some(String::from("data-url")),
+            HashMap::new(),
+        );
+        let html = r#"<a class="result__url" href="/l/?u=1"> www.rust-lang.org </a>"#;
+
+        let links = search
+            .links_from_html(html, "https://html.duckduckgo.com/html/?q=rust")
+            .unwrap();
+
+        assert_eq!(links[0].url, "https://www.rust-lang.org");
+        assert_eq!(links[0].title, None);
+    }
+
+    #[test]
+    fn test_links_from_html_no_results_is_error() {
+        assert!(matches!(
+            mdn().links_from_html("<html><body></body></html>", MDN_SEARCH),
+            Err(SearchError(_))
+        ));
+    }
+}
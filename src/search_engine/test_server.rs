@@ -0,0 +1,165 @@
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig, ServerConnection, StreamOwned};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Arc;
+use std::thread;
+
+/// Starts a local stand-in server that answers a single request with the given response and
+/// returns its base url.
+pub fn serve_once(status: &str, content_type: &str, body: &[u8]) -> String {
+    serve_with_headers(status, &[("Content-Type", content_type)], body)
+}
+
+/// As [`serve_once`], answering with the given headers.
+pub fn serve_with_headers(status: &str, headers: &[(&str, &str)], body: &[u8]) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind test server");
+    let address = listener
+        .local_addr()
+        .expect("Failed to get test server address");
+    let headers = headers
+        .iter()
+        .map(|(name, value)| format!("{name}: {value}\r\n"))
+        .collect::<String>();
+    let mut response = format!(
+        "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    response.extend_from_slice(body);
+    thread::spawn(move || {
+        if let Ok((mut stream, _)) = listener.accept() {
+            read_request_head(&stream);
+            _ = stream.write_all(&response);
+        }
+    });
+    format!("http://{address}")
+}
+
+/// Starts a local stand-in server that answers one request after another with the given statuses
+/// and headers, in order, and returns its base url.
+pub fn serve_sequence(responses: Vec<(&str, Vec<(&str, &str)>)>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind test server");
+    let address = listener
+        .local_addr()
+        .expect("Failed to get test server address");
+    let responses = responses
+        .into_iter()
+        .map(|(status, headers)| {
+            let headers = headers
+                .iter()
+                .map(|(name, value)| format!("{name}: {value}\r\n"))
+                .collect::<String>();
+            format!("HTTP/1.1 {status}\r\n{headers}Content-Length: 0\r\nConnection: close\r\n\r\n")
+        })
+        .collect::<Vec<String>>();
+    thread::spawn(move || {
+        for response in responses {
+            if let Ok((mut stream, _)) = listener.accept() {
+                read_request_head(&stream);
+                _ = stream.write_all(response.as_bytes());
+            }
+        }
+    });
+    format!("http://{address}")
+}
+
+/// Starts a local stand-in proxy that accepts a single CONNECT request and answers the tunnelled
+/// request with the given body. Returns the proxy url, and the CONNECT line it was sent.
+pub fn serve_proxy_once(content_type: &str, body: &[u8]) -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind test proxy");
+    let address = listener
+        .local_addr()
+        .expect("Failed to get test proxy address");
+    let mut response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    response.extend_from_slice(body);
+    let (sender, receiver) = channel();
+    thread::spawn(move || {
+        if let Ok((mut stream, _)) = listener.accept() {
+            let connect = read_request_head(&stream);
+            _ = stream.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n");
+            read_request_head(&stream);
+            _ = stream.write_all(&response);
+            _ = sender.send(connect);
+        }
+    });
+    (format!("http://{address}"), receiver)
+}
+
+/// Starts a local stand-in https server, with a certificate from the test CA in tests/data/tls,
+/// that answers a single request with the given html. It can require a client certificate from
+/// the same CA. Returns its base url.
+pub fn serve_tls_once(require_client_certificate: bool, body: &[u8]) -> String {
+    let tls_file = |name: &str| format!("{}/tests/data/tls/{name}", env!("CARGO_MANIFEST_DIR"));
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .expect("Failed to set test server protocols");
+    let builder = if require_client_certificate {
+        let mut roots = RootCertStore::empty();
+        roots
+            .add(CertificateDer::from_pem_file(tls_file("ca.pem")).expect("Failed to read CA"))
+            .expect("Failed to add CA");
+        let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+            .build()
+            .expect("Failed to create client verifier");
+        builder.with_client_cert_verifier(verifier)
+    } else {
+        builder.with_no_client_auth()
+    };
+    let certificates = CertificateDer::pem_file_iter(tls_file("server.pem"))
+        .expect("Failed to read server certificate")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Failed to parse server certificate");
+    let key =
+        PrivateKeyDer::from_pem_file(tls_file("server.key")).expect("Failed to read server key");
+    let config = Arc::new(
+        builder
+            .with_single_cert(certificates, key)
+            .expect("Failed to create test server config"),
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind test server");
+    let address = listener
+        .local_addr()
+        .expect("Failed to get test server address");
+    let mut response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    response.extend_from_slice(body);
+    thread::spawn(move || {
+        if let Ok((stream, _)) = listener.accept() {
+            let connection = ServerConnection::new(config).expect("Failed to create connection");
+            let mut stream = StreamOwned::new(connection, stream);
+            let mut reader = BufReader::new(&mut stream);
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                line.clear();
+            }
+            _ = stream.write_all(&response);
+            _ = stream.flush();
+        }
+    });
+    format!("https://localhost:{}", address.port())
+}
+
+// Consumes the request head before answering, returning the request line.
+fn read_request_head(stream: &TcpStream) -> String {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    _ = reader.read_line(&mut request_line);
+    let mut line = String::new();
+    while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+        line.clear();
+    }
+    request_line.trim_end().to_string()
+}
@@ -0,0 +1,144 @@
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use parking_lot::{Condvar, Mutex};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use ureq::http::Uri;
+
+pub const DEFAULT_MAX_CONNECTIONS: usize = 2;
+pub const DEFAULT_MIN_DELAY: u64 = 250;
+
+static HOSTS: Lazy<DashMap<String, Arc<Host>>> = Lazy::new(DashMap::new);
+
+/// How many requests can be made to a host at the same time, and the least time between the
+/// start of each request to it. These are shared by every request, so preloading pages and
+/// searching can not overload a site between them.
+#[derive(Debug, Clone)]
+pub struct ThrottleSettings {
+    max_connections: usize,
+    min_delay: Duration,
+}
+
+impl Default for ThrottleSettings {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CONNECTIONS, DEFAULT_MIN_DELAY)
+    }
+}
+
+impl ThrottleSettings {
+    pub fn new(max_connections: usize, min_delay_ms: u64) -> Self {
+        Self {
+            // A limit of 0 would block every request.
+            max_connections: max_connections.max(1),
+            min_delay: Duration::from_millis(min_delay_ms),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Host {
+    state: Mutex<HostState>,
+    released: Condvar,
+}
+
+#[derive(Default)]
+struct HostState {
+    active: usize,
+    next_start: Option<Instant>,
+}
+
+/// Held while a request to the host is in progress, letting the next request start when dropped.
+pub struct HostPermit {
+    host: Arc<Host>,
+}
+
+impl Drop for HostPermit {
+    fn drop(&mut self) {
+        self.host.state.lock().active -= 1;
+        self.host.released.notify_one();
+    }
+}
+
+/// Waits until a request can be made to the host of the url.
+pub fn wait_for_host(url: &str, settings: &ThrottleSettings) -> HostPermit {
+    let name = host_name(url);
+    let host = HOSTS.entry(name.clone()).or_default().clone();
+    let start = {
+        let mut state = host.state.lock();
+        if state.active >= settings.max_connections {
+            log::debug!("Waiting for a connection to {name}");
+        }
+        while state.active >= settings.max_connections {
+            host.released.wait(&mut state);
+        }
+        state.active += 1;
+        // The start time is reserved while locked, so requests waiting on the delay are still
+        // spaced out from each other.
+        let now = Instant::now();
+        let start = state.next_start.map_or(now, |next| next.max(now));
+        state.next_start = Some(start + settings.min_delay);
+        start
+    };
+    let delay = start.saturating_duration_since(Instant::now());
+    if !delay.is_zero() {
+        log::debug!("Waiting {delay:?} before the next request to {name}");
+        thread::sleep(delay);
+    }
+    HostPermit { host }
+}
+
+fn host_name(url: &str) -> String {
+    url.parse::<Uri>()
+        .ok()
+        .and_then(|uri| {
+            uri.authority()
+                .map(|authority| authority.as_str().to_lowercase())
+        })
+        .unwrap_or_else(|| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requests_are_spaced_by_min_delay() {
+        let settings = ThrottleSettings::new(5, 50);
+        let start = Instant::now();
+
+        drop(wait_for_host("https://delay.example.com/one", &settings));
+        drop(wait_for_host("https://delay.example.com/two", &settings));
+        drop(wait_for_host("https://delay.example.com/three", &settings));
+
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_connections_are_limited_per_host() {
+        let settings = ThrottleSettings::new(1, 0);
+        let permit = wait_for_host("https://limit.example.com/one", &settings);
+        let waiting_settings = settings.clone();
+        let start = Instant::now();
+        let waiting = thread::spawn(move || {
+            drop(wait_for_host(
+                "https://limit.example.com/two",
+                &waiting_settings,
+            ));
+            Instant::now()
+        });
+
+        // Other hosts are not held up by the busy host.
+        drop(wait_for_host("https://other.example.com", &settings));
+        thread::sleep(Duration::from_millis(50));
+        drop(permit);
+
+        assert!(waiting.join().unwrap() - start >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_host_name_includes_port() {
+        assert_eq!(host_name("https://Docs.rs/std"), "docs.rs");
+        assert_eq!(host_name("http://127.0.0.1:8080/page"), "127.0.0.1:8080");
+    }
+}
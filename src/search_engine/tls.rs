@@ -0,0 +1,262 @@
+use crate::config::files::config_location;
+use crate::errors::error::IsError;
+use crate::errors::error::IsError::Scrape;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ureq::tls::{parse_pem, Certificate, ClientCert, PemItem, PrivateKey, RootCerts, TlsConfig};
+
+// The config is built once for each settings, as loading the certificates reads them from disk.
+static TLS_CONFIGS: Lazy<DashMap<TlsSettings, TlsConfig>> = Lazy::new(DashMap::new);
+static NATIVE_ROOTS: Lazy<Vec<Certificate<'static>>> = Lazy::new(load_native_roots);
+
+/// The certificates to use for the TLS connection to a site. Paths are relative to the config
+/// directory unless they are absolute.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TlsSettings {
+    ca_bundle: Option<PathBuf>,
+    client_certificate: Option<PathBuf>,
+    client_key: Option<PathBuf>,
+    insecure_skip_verify: bool,
+}
+
+impl TlsSettings {
+    pub fn new(
+        ca_bundle: Option<String>,
+        client_certificate: Option<String>,
+        client_key: Option<String>,
+        insecure_skip_verify: bool,
+    ) -> Self {
+        Self {
+            ca_bundle: ca_bundle.map(config_path),
+            client_certificate: client_certificate.map(config_path),
+            client_key: client_key.map(config_path),
+            insecure_skip_verify,
+        }
+    }
+
+    fn is_default(&self) -> bool {
+        self.ca_bundle.is_none() && self.client_certificate.is_none() && !self.insecure_skip_verify
+    }
+}
+
+fn config_path(path: String) -> PathBuf {
+    config_location().join(path)
+}
+
+/// Creates the TLS config for the url, or None when the site uses the platform certificates.
+pub fn tls_config_for(url: &str, settings: &TlsSettings) -> Result<Option<TlsConfig>, IsError> {
+    if settings.is_default() {
+        return Ok(None);
+    }
+    if let Some(tls_config) = TLS_CONFIGS.get(settings) {
+        return Ok(Some(tls_config.clone()));
+    }
+    let tls_config = build_tls_config(settings)?;
+    // Warned once for the settings rather than for every request, as preloading would flood the
+    // log.
+    if settings.insecure_skip_verify {
+        log::warn!(
+            "!!! TLS CERTIFICATE VERIFICATION IS DISABLED FOR {url} AND ANY SITE WITH THE SAME [tls] \
+             SETTINGS - the connection is not secure, and anyone on the network can read or change \
+             the page. Remove insecure_skip_verify from the [tls] config as soon as possible !!!"
+        );
+    }
+    TLS_CONFIGS.insert(settings.clone(), tls_config.clone());
+    Ok(Some(tls_config))
+}
+
+fn build_tls_config(settings: &TlsSettings) -> Result<TlsConfig, IsError> {
+    let mut builder = TlsConfig::builder().root_certs(RootCerts::PlatformVerifier);
+    if let Some(ca_bundle) = &settings.ca_bundle {
+        builder = builder.root_certs(RootCerts::new_with_certs(&trusted_certificates(ca_bundle)?));
+    }
+    if let Some(client_certificate) = &settings.client_certificate {
+        // The key can be given in the same file as the certificate.
+        let key_file = settings.client_key.as_ref().unwrap_or(client_certificate);
+        let chain = load_certificates(client_certificate)?;
+        let key = load_private_key(key_file)?;
+        builder = builder.client_cert(Some(ClientCert::new_with_certs(&chain, key)));
+    }
+    Ok(builder
+        .disable_verification(settings.insecure_skip_verify)
+        .build())
+}
+
+// The bundle is trusted as well as the system certificates, so a site signed by a private CA
+// can still redirect to, or load from, public sites.
+fn trusted_certificates(ca_bundle: &Path) -> Result<Vec<Certificate<'static>>, IsError> {
+    let mut certificates = load_certificates(ca_bundle)?;
+    certificates.extend(NATIVE_ROOTS.iter().cloned());
+    Ok(certificates)
+}
+
+fn load_native_roots() -> Vec<Certificate<'static>> {
+    let native = rustls_native_certs::load_native_certs();
+    for error in &native.errors {
+        log::warn!("Could not load a system certificate: {error}");
+    }
+    native
+        .certs
+        .iter()
+        .map(|certificate| Certificate::from_der(certificate.as_ref()).to_owned())
+        .collect()
+}
+
+fn read_pem(path: &Path) -> Result<Vec<PemItem<'static>>, IsError> {
+    let pem =
+        fs::read(path).map_err(|e| Scrape(format!("Could not read {}: {e}", path.display())))?;
+    parse_pem(&pem)
+        .collect::<Result<Vec<PemItem>, _>>()
+        .map_err(|e| Scrape(format!("Could not parse {}: {e}", path.display())))
+}
+
+fn load_certificates(path: &Path) -> Result<Vec<Certificate<'static>>, IsError> {
+    let certificates = read_pem(path)?
+        .into_iter()
+        .filter_map(|item| match item {
+            PemItem::Certificate(certificate) => Some(certificate),
+            _ => None,
+        })
+        .collect::<Vec<Certificate>>();
+    if certificates.is_empty() {
+        Err(Scrape(format!(
+            "No certificates found in {}",
+            path.display()
+        )))
+    } else {
+        Ok(certificates)
+    }
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKey<'static>, IsError> {
+    read_pem(path)?
+        .into_iter()
+        .find_map(|item| match item {
+            PemItem::PrivateKey(key) => Some(key),
+            _ => None,
+        })
+        .ok_or_else(|| Scrape(format!("No private key found in {}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::site::CallConfig;
+    use crate::search_engine::scrape::get_request;
+    use crate::search_engine::test_server::serve_tls_once;
+
+    const CA: &str = "tests/data/tls/ca.pem";
+    const CLIENT_CERTIFICATE: &str = "tests/data/tls/client.pem";
+    const CLIENT_KEY: &str = "tests/data/tls/client.key";
+
+    fn absolute(path: &str) -> Option<String> {
+        Some(format!("{}/{path}", env!("CARGO_MANIFEST_DIR")))
+    }
+
+    fn call(tls: TlsSettings) -> CallConfig {
+        CallConfig {
+            tls,
+            ..CallConfig::default()
+        }
+    }
+
+    fn fetch(url: &str, tls: TlsSettings) -> Result<String, String> {
+        get_request(url, &call(tls))
+            .map_err(|e| e.to_string())?
+            .call()
+            .map_err(|e| e.to_string())
+            .and_then(|response| {
+                response
+                    .into_body()
+                    .read_to_string()
+                    .map_err(|e| e.to_string())
+            })
+    }
+
+    #[test]
+    fn test_private_ca_is_trusted_with_bundle() {
+        let url = serve_tls_once(false, b"<p>Private CA</p>");
+
+        let page = fetch(&url, TlsSettings::new(absolute(CA), None, None, false));
+
+        assert_eq!(page.unwrap(), "<p>Private CA</p>");
+    }
+
+    #[test]
+    fn test_private_ca_is_rejected_without_bundle() {
+        let url = serve_tls_once(false, b"<p>Private CA</p>");
+
+        assert!(fetch(&url, TlsSettings::default()).is_err());
+    }
+
+    #[test]
+    fn test_insecure_skip_verify_accepts_unknown_ca() {
+        let url = serve_tls_once(false, b"<p>Unverified</p>");
+
+        let page = fetch(&url, TlsSettings::new(None, None, None, true));
+
+        assert_eq!(page.unwrap(), "<p>Unverified</p>");
+    }
+
+    #[test]
+    fn test_client_certificate_is_sent() {
+        let url = serve_tls_once(true, b"<p>Mutual TLS</p>");
+        let with_certificate = TlsSettings::new(
+            absolute(CA),
+            absolute(CLIENT_CERTIFICATE),
+            absolute(CLIENT_KEY),
+            false,
+        );
+
+        assert_eq!(fetch(&url, with_certificate).unwrap(), "<p>Mutual TLS</p>");
+
+        let url = serve_tls_once(true, b"<p>Mutual TLS</p>");
+        assert!(fetch(&url, TlsSettings::new(absolute(CA), None, None, false)).is_err());
+    }
+
+    #[test]
+    fn test_bundle_is_added_to_system_certificates() {
+        let bundle = load_certificates(Path::new(&absolute(CA).unwrap())).unwrap();
+
+        let trusted = trusted_certificates(Path::new(&absolute(CA).unwrap())).unwrap();
+
+        assert_eq!(trusted.len(), bundle.len() + NATIVE_ROOTS.len());
+        assert!(bundle
+            .iter()
+            .all(|ca| trusted.iter().any(|t| t.der() == ca.der())));
+    }
+
+    #[test]
+    fn test_tls_config_is_built_once() {
+        let settings = TlsSettings::new(absolute(CA), None, None, false);
+
+        tls_config_for("https://docs.internal", &settings).unwrap();
+
+        assert!(TLS_CONFIGS.contains_key(&settings));
+        assert!(tls_config_for("https://docs.internal/other", &settings)
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_missing_bundle_errors() {
+        let settings = TlsSettings::new(absolute("tests/data/tls/missing.pem"), None, None, false);
+
+        assert!(matches!(
+            tls_config_for("https://docs.internal", &settings),
+            Err(Scrape(_))
+        ));
+    }
+
+    #[test]
+    fn test_certificate_without_key_errors() {
+        let settings = TlsSettings::new(None, absolute(CLIENT_CERTIFICATE), None, false);
+
+        assert!(matches!(
+            tls_config_for("https://docs.internal", &settings),
+            Err(Scrape(_))
+        ));
+    }
+}
@@ -0,0 +1,270 @@
+use crate::config::load::Config;
+use crate::errors::error::IsError;
+use crate::errors::error::IsError::Search as SearchError;
+use crate::search_engine::link::Link;
+use crate::search_engine::locale::SearchLocale;
+use crate::search_engine::scrape::get_text;
+use crate::search_engine::search_type::Search;
+use once_cell::sync::Lazy;
+use serde_json::from_str;
+use std::collections::HashMap;
+
+#[derive(serde::Deserialize)]
+struct SearchResult {
+    #[serde(default)]
+    query: Option<SearchQuery>,
+}
+
+#[derive(serde::Deserialize)]
+struct SearchQuery {
+    #[serde(default)]
+    redirects: Vec<Redirect>,
+    #[serde(default)]
+    pages: Vec<SearchPage>,
+}
+
+#[derive(serde::Deserialize)]
+struct Redirect {
+    #[serde(default)]
+    index: Option<usize>,
+    to: String,
+}
+
+#[derive(serde::Deserialize)]
+struct SearchPage {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    fullurl: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    index: Option<usize>,
+    #[serde(default)]
+    pageprops: Option<PageProps>,
+}
+
+#[derive(serde::Deserialize)]
+struct PageProps {
+    #[serde(default)]
+    disambiguation: Option<serde_json::Value>,
+}
+
+impl SearchPage {
+    fn is_disambiguation(&self) -> bool {
+        self.pageprops
+            .as_ref()
+            .is_some_and(|props| props.disambiguation.is_some())
+    }
+}
+
+pub(crate) const ENGINE_NAME: &str = "wikipedia";
+const PAGE_SIZE: usize = 10;
+const API_PATH: &str = "/w/api.php";
+
+static LOCALE: Lazy<&SearchLocale> = Lazy::new(Config::get_search_locale);
+
+/// Searches a wiki through the MediaWiki api, returning the article urls directly. The wiki can be
+/// set to any MediaWiki instance, otherwise the Wikipedia for the search language is used.
+#[derive(Debug, Clone)]
+pub struct WikipediaSearch {
+    base_url: Option<String>,
+}
+
+impl WikipediaSearch {
+    pub fn new(base_url: Option<&str>) -> Self {
+        Self {
+            base_url: base_url
+                .map(|url| url.trim().trim_end_matches('/').to_string())
+                .filter(|url| !url.is_empty()),
+        }
+    }
+
+    // The url can be the host of the wiki, or the full path to its api.php for wikis that are not
+    // installed under /w.
+    fn api_url(&self, locale: &SearchLocale) -> String {
+        match &self.base_url {
+            Some(url) if url.ends_with("api.php") => url.clone(),
+            Some(url) => format!("{url}{API_PATH}"),
+            None => format!(
+                "https://{}.wikipedia.org{API_PATH}",
+                locale.language.as_deref().unwrap_or("en")
+            ),
+        }
+    }
+
+    // Redirects are followed to the article they point to, and the disambiguation property is
+    // requested so those pages can be put after the articles.
+    fn search_url(&self, query: &str, page: usize, locale: &SearchLocale) -> String {
+        format!(
+            "{}?action=query&format=json&formatversion=2&redirects=1&generator=search&gsrsearch={query}&gsrlimit={PAGE_SIZE}&gsroffset={}&prop=info|pageprops|description&inprop=url&ppprop=disambiguation",
+            self.api_url(locale),
+            page * PAGE_SIZE
+        )
+    }
+
+    // Site restrictions are checked against the results after the search, and the wiki search
+    // would read them as words to find, so they are dropped from the query.
+    fn article_query(query: &str) -> String {
+        query
+            .split('+')
+            .filter(|word| {
+                !word.is_empty()
+                    && *word != "OR"
+                    && !word.starts_with("site:")
+                    && !word.starts_with("-site:")
+            })
+            .collect::<Vec<&str>>()
+            .join("+")
+    }
+
+    fn search_result_to_links(search_result: SearchResult) -> Vec<Link> {
+        let Some(query) = search_result.query else {
+            return vec![];
+        };
+        // Older wikis keep the search position on the redirect rather than the article it
+        // points to.
+        let redirects = query
+            .redirects
+            .iter()
+            .filter_map(|redirect| Some((redirect.to.as_str(), redirect.index?)))
+            .collect::<HashMap<&str, usize>>();
+        let mut pages = query
+            .pages
+            .into_iter()
+            .filter(|page| !page.fullurl.is_empty())
+            .map(|mut page| {
+                page.index = page
+                    .index
+                    .or_else(|| redirects.get(page.title.as_str()).copied());
+                page
+            })
+            .collect::<Vec<SearchPage>>();
+        // The pages are not returned in the order of the search. Disambiguation pages only list
+        // other articles, so these are put last.
+        pages.sort_by_key(|page| (page.is_disambiguation(), page.index.unwrap_or(usize::MAX)));
+        pages
+            .iter()
+            .map(|page| {
+                let snippet = if page.is_disambiguation() {
+                    "Disambiguation page"
+                } else {
+                    &page.description
+                };
+                Link::from_search(&page.fullurl, &page.title, snippet, ENGINE_NAME)
+            })
+            .collect()
+    }
+
+    fn request_results(&self, query: &str, page: usize) -> Result<String, IsError> {
+        get_text(
+            &self.search_url(query, page, &LOCALE),
+            &[("Accept", "application/json")],
+        )
+    }
+}
+
+impl Search for WikipediaSearch {
+    fn search(&self, query: &str, page: usize) -> Result<Vec<Link>, IsError> {
+        let query = Self::article_query(query);
+        if query.is_empty() {
+            return Err(SearchError(String::from("No words to search for")));
+        }
+        let links = self
+            .request_results(&query, page)
+            .and_then(|json| {
+                from_str::<SearchResult>(&json).map_err(|e| SearchError(e.to_string()))
+            })
+            .map(Self::search_result_to_links)
+            .map_err(|e| SearchError(e.to_string()))?;
+        if links.is_empty() {
+            Err(SearchError(String::from("No articles found")))
+        } else {
+            Ok(links)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_engine::test_server::serve_once;
+
+    #[test]
+    fn test_wikipedia_links_in_search_order() {
+        let body = r#"{"batchcomplete": true, "query": {
+            "redirects": [{"index": 1, "from": "Einstein", "to": "Albert Einstein"}],
+            "pages": [
+                {"pageid": 3, "title": "Einstein (disambiguation)", "index": 2, "fullurl": "https://en.wikipedia.org/wiki/Einstein_(disambiguation)", "pageprops": {"disambiguation": ""}},
+                {"pageid": 2, "title": "Einstein family", "index": 3, "fullurl": "https://en.wikipedia.org/wiki/Einstein_family"},
+                {"pageid": 1, "title": "Albert Einstein", "fullurl": "https://en.wikipedia.org/wiki/Albert_Einstein", "description": "German-born physicist (1879–1955)"}
+            ]
+        }}"#;
+        let base_url = serve_once("200 OK", "application/json", body.as_bytes());
+
+        let links = WikipediaSearch::new(Some(&base_url))
+            .search("einstein", 0)
+            .unwrap();
+
+        let urls = links.iter().map(|l| l.url.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            urls,
+            vec![
+                "https://en.wikipedia.org/wiki/Albert_Einstein",
+                "https://en.wikipedia.org/wiki/Einstein_family",
+                "https://en.wikipedia.org/wiki/Einstein_(disambiguation)"
+            ]
+        );
+        assert_eq!(links[0].title.as_deref(), Some("Albert Einstein"));
+        assert_eq!(
+            links[0].snippet.as_deref(),
+            Some("German-born physicist (1879–1955)")
+        );
+        assert_eq!(links[0].engine.as_deref(), Some("wikipedia"));
+        assert_eq!(links[1].snippet, None);
+        assert_eq!(links[2].snippet.as_deref(), Some("Disambiguation page"));
+    }
+
+    #[test]
+    fn test_wikipedia_without_results_errors() {
+        let base_url = serve_once("200 OK", "application/json", br#"{"batchcomplete": true}"#);
+
+        let result = WikipediaSearch::new(Some(&base_url)).search("xyzzyq", 0);
+
+        assert!(matches!(result, Err(SearchError(_))));
+    }
+
+    #[test]
+    fn test_wikipedia_api_url() {
+        let locale = SearchLocale::new(None, Some(String::from("de")), None);
+        assert_eq!(
+            WikipediaSearch::new(None).api_url(&locale),
+            "https://de.wikipedia.org/w/api.php"
+        );
+        assert_eq!(
+            WikipediaSearch::new(Some("https://wiki.example.com/")).api_url(&locale),
+            "https://wiki.example.com/w/api.php"
+        );
+        assert_eq!(
+            WikipediaSearch::new(Some("https://wiki.example.com/mediawiki/api.php"))
+                .api_url(&locale),
+            "https://wiki.example.com/mediawiki/api.php"
+        );
+    }
+
+    #[test]
+    fn test_wikipedia_search_url_pages_with_offset() {
+        assert_eq!(
+            WikipediaSearch::new(None).search_url("rust", 2, &SearchLocale::default()),
+            "https://en.wikipedia.org/w/api.php?action=query&format=json&formatversion=2&redirects=1&generator=search&gsrsearch=rust&gsrlimit=10&gsroffset=20&prop=info|pageprops|description&inprop=url&ppprop=disambiguation"
+        );
+    }
+
+    #[test]
+    fn test_article_query_drops_site_terms() {
+        assert_eq!(
+            WikipediaSearch::article_query("albert+einstein+site:en.wikipedia.org+OR+-site:x.com"),
+            "albert+einstein"
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use pulldown_cmark::{html, Options, Parser};
+
+/// Converts markdown to html, so it is shown with the same styles and code highlighting as a
+/// page. Tables, strikethrough and task lists from GitHub flavoured markdown are supported.
+pub fn to_html(markdown: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut output = String::new();
+    html::push_html(&mut output, Parser::new_ext(markdown, options));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headings_and_paragraphs() {
+        assert_eq!(
+            to_html("# Title\n\nSome *text*\nover lines.\n\nSub\n---"),
+            "<h1>Title</h1>\n<p>Some <em>text</em>\nover lines.</p>\n<h2>Sub</h2>\n"
+        );
+    }
+
+    #[test]
+    fn test_fenced_code_keeps_language() {
+        assert_eq!(
+            to_html("```rust\nlet a = 1 < 2;\n```"),
+            "<pre><code class=\"language-rust\">let a = 1 &lt; 2;\n</code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn test_nested_lists() {
+        assert_eq!(
+            to_html("- One\n- Two\n  1. Nested\n- Three"),
+            "<ul>\n<li>One</li>\n<li>Two\n<ol>\n<li>Nested</li>\n</ol>\n</li>\n<li>Three</li>\n</ul>\n"
+        );
+    }
+
+    #[test]
+    fn test_non_ascii_indentation() {
+        let html = to_html("- a\n\u{3000}\u{3000}x\n\n\u{3000}- b");
+
+        assert!(html.contains("<li>a"));
+        assert!(html.contains('x'));
+        assert!(html.contains('b'));
+    }
+
+    #[test]
+    fn test_github_extensions() {
+        let html = to_html("| A | B |\n|---|---|\n| 1 | ~~2~~ |");
+
+        assert!(html.contains("<table>"));
+        assert!(html.contains("<th>A</th>"));
+        assert!(html.contains("<del>2</del>"));
+    }
+}
@@ -1,6 +1,8 @@
 pub mod cache;
 mod filter;
 mod format;
+mod html;
+mod markdown;
 pub mod page;
 pub mod pretty_print;
 mod syntax_highlight;
@@ -4,17 +4,18 @@ use crate::config::load::{Config, ExtractionConfig};
 use crate::errors::error::IsError;
 use crate::errors::error::IsError::{Io, Scrape};
 use crate::page::structure::{Line, Span};
+use crate::search_engine::feed::{is_feed, is_forced, parse_feed, record_feed};
 use crate::search_engine::link::HtmlSource;
 use crate::search_engine::scrape;
-use crate::search_engine::scrape::scrape;
+use crate::search_engine::scrape::{decode_html, scrape};
 use crate::transform::filter::filter;
 use crate::transform::format::Formatter;
 use crate::transform::syntax_highlight::SyntaxHighlighter;
+use crate::transform::{html, markdown};
 use ratatui::text::{Line as RatLine, Text};
 use ratatui::widgets::Paragraph;
 use scraper::{ElementRef, Html, Selector};
 use std::cmp::max;
-use std::fs;
 
 #[derive(Clone)]
 pub struct PageExtractor {
@@ -46,37 +47,138 @@ impl PageExtractor {
     }
 
     fn get_tui_text(&self, html_source: &HtmlSource) -> (String, Vec<Line>) {
-        let html_result: Result<String, IsError> = match html_source {
+        let content_result: Result<String, IsError> = match html_source {
             HtmlSource::LinkSource(_) => scrape(html_source),
-            HtmlSource::FileSource(file) => fs::read_to_string(&file.file_path).map_err(Io),
+            HtmlSource::FileSource(file) => file
+                .read()
+                .and_then(|bytes| decode_html(&bytes, None))
+                .map_err(Io),
         };
+
+        content_result
+            .map(|content| PageExtractor::sanitize(&content))
+            .and_then(|content| match ContentKind::of(html_source, &content) {
+                ContentKind::Html => self.html_text(html_source, &content),
+                ContentKind::Markdown => Ok(self.markdown_text(html_source, &content)),
+                ContentKind::Json => Ok(Self::json_text(html_source, &content)),
+                ContentKind::Text => Ok(Self::plain_text(html_source, &content)),
+                ContentKind::Feed => self.feed_text(html_source, &content),
+            })
+            .unwrap_or_else(|err| {
+                if let HtmlSource::LinkSource(_) = html_source {
+                    scrape::cache_purge(html_source);
+                }
+                (
+                    String::from("Failed to retrieve"),
+                    vec![Line::from_single(Span::from(&err.to_string()))],
+                )
+            })
+    }
+
+    fn html_text(
+        &self,
+        html_source: &HtmlSource,
+        content: &str,
+    ) -> Result<(String, Vec<Line>), IsError> {
         let selector = Selector::parse("title").expect("invalid title selector");
         log::debug!("Preparing to parse HTML");
-        let html = html_result
-            .map(|html| PageExtractor::sanitize(&html))
-            .map(|sanitized| Html::parse_document(&sanitized));
+        let html = Html::parse_document(content);
         log::debug!("HTML parsed");
+        let title = Self::extract_title(&selector, &html);
+        let text = self.extract_text(html_source, &html)?;
+        Ok((title, text))
+    }
 
-        html.and_then(|html| {
-            let title = Self::extract_title(&selector, &html);
-            let text = self.extract_text(html_source, &html)?;
-            Ok((title, text))
-        })
-        .unwrap_or_else(|err| {
-            if let HtmlSource::LinkSource(_) = html_source {
-                scrape::cache_purge(html_source);
-            }
-            (
-                String::from("Failed to retrieve"),
-                vec![Line::from_single(Span::from(&err.to_string()))],
-            )
-        })
+    // Markdown is shown as the html it converts to, so it is styled in the same way as a page.
+    // There are no site specific elements in markdown, so the selectors are not used.
+    fn markdown_text(&self, html_source: &HtmlSource, content: &str) -> (String, Vec<Line>) {
+        let html = Html::parse_document(&markdown::to_html(content));
+        let heading = Selector::parse("h1").expect("invalid heading selector");
+        let body = Selector::parse("body").expect("invalid body selector");
+        let title = html
+            .select(&heading)
+            .next()
+            .map(|heading| heading.text().collect::<String>())
+            .filter(|title| !title.trim().is_empty())
+            .unwrap_or_else(|| Self::source_name(html_source));
+        let text = self.process_elements(html_source, html.select(&body).collect());
+        (title, text)
+    }
+
+    // The json is reformatted to be readable, unless it is invalid when it is shown as it was sent.
+    fn json_text(html_source: &HtmlSource, content: &str) -> (String, Vec<Line>) {
+        let pretty = serde_json::from_str::<serde_json::Value>(content)
+            .and_then(|value| serde_json::to_string_pretty(&value))
+            .unwrap_or_else(|_| content.to_string());
+        let syntax_config = html_source.get_config().get_syntax().clone();
+        let text = SyntaxHighlighter::new(syntax_config).highlight_code(&pretty, "json");
+        (Self::source_name(html_source), text)
+    }
+
+    // The feed is listed as html so the entries are styled like a page. The entries are recorded, so
+    // they can be picked from a list in the tui.
+    fn feed_text(
+        &self,
+        html_source: &HtmlSource,
+        content: &str,
+    ) -> Result<(String, Vec<Line>), IsError> {
+        let feed = parse_feed(&html_source.get_final_url(), content)?;
+        if feed.entries.is_empty() {
+            return Err(Scrape(String::from("The feed has no entries.")));
+        }
+        let listing = feed
+            .entries
+            .iter()
+            .map(|entry| {
+                let url = html::escape(&entry.url);
+                let title = html::escape(entry.title.as_deref().unwrap_or(&entry.url));
+                let date = entry
+                    .published
+                    .as_deref()
+                    .map(|date| format!("<em>{}</em> ", html::escape(date)))
+                    .unwrap_or_default();
+                let summary = html::escape(entry.snippet.as_deref().unwrap_or_default());
+                format!("<h3><a href=\"{url}\">{title}</a></h3><p>{date}{url}</p><p>{summary}</p>")
+            })
+            .collect::<String>();
+        let html = Html::parse_document(&listing);
+        let body = Selector::parse("body").expect("invalid body selector");
+        let text = self.process_elements(html_source, html.select(&body).collect());
+        let title = Some(feed.title.clone())
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| Self::source_name(html_source));
+        record_feed(html_source, feed);
+        Ok((title, text))
+    }
+
+    fn plain_text(html_source: &HtmlSource, content: &str) -> (String, Vec<Line>) {
+        let text = content
+            .lines()
+            .map(|line| Line::from_single(Span::from(line)))
+            .collect();
+        (Self::source_name(html_source), text)
+    }
+
+    // Content without a title is named after the file, or the last part of the url path.
+    fn source_name(html_source: &HtmlSource) -> String {
+        let name = match html_source {
+            HtmlSource::FileSource(file) if file.is_stdin() => String::from("stdin"),
+            HtmlSource::FileSource(file) => file.file_path.clone(),
+            HtmlSource::LinkSource(_) => html_source.get_final_url(),
+        };
+        let name = name.split(['?', '#']).next().unwrap_or_default();
+        name.trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty())
+            .unwrap_or(name)
+            .to_string()
     }
 
     fn extract_text(&self, html_source: &HtmlSource, html: &Html) -> Result<Vec<Line>, IsError> {
         filter(
             html,
-            self.config().get_selectors(html_source.get_url()),
+            self.config().get_selectors(&html_source.get_final_url()),
         )
             .map(|elements| self.process_elements(html_source, elements))
             .and_then(|text| {
@@ -179,6 +281,78 @@ impl PageExtractor {
     }
 }
 
+/// How the content is shown, from the media type it was sent with or the extension of the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentKind {
+    Html,
+    Json,
+    Markdown,
+    Text,
+    Feed,
+}
+
+impl ContentKind {
+    fn of(html_source: &HtmlSource, content: &str) -> Self {
+        if is_forced(html_source) {
+            return Self::Feed;
+        }
+        match html_source {
+            HtmlSource::FileSource(file) => {
+                Self::from_extension(&file.file_path).unwrap_or_else(|| Self::sniff(content))
+            }
+            HtmlSource::LinkSource(_) => {
+                let url = html_source.get_final_url();
+                match scrape::content_type(&url)
+                    .or_else(|| scrape::content_type(html_source.get_url()))
+                {
+                    Some(media_type) => Self::from_media_type(&media_type, &url, content),
+                    // Without a media type it is found from the url or the content.
+                    None => Self::from_extension(&url).unwrap_or_else(|| Self::sniff(content)),
+                }
+            }
+        }
+    }
+
+    fn from_media_type(media_type: &str, url: &str, content: &str) -> Self {
+        match media_type {
+            "application/rss+xml" | "application/atom+xml" | "application/rdf+xml" => Self::Feed,
+            // Feeds are also sent as generic xml, so the root element is checked.
+            "application/xml" | "text/xml" => Self::sniff(content),
+            "application/json" => Self::Json,
+            json if json.ends_with("+json") => Self::Json,
+            "text/markdown" | "text/x-markdown" => Self::Markdown,
+            // Raw files, such as on GitHub, are often sent as plain text whatever they contain.
+            "text/plain" => Self::from_extension(url).unwrap_or(Self::Text),
+            _ => Self::Html,
+        }
+    }
+
+    fn from_extension(path: &str) -> Option<Self> {
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        let extension = path.rsplit_once('.')?.1.to_lowercase();
+        match extension.as_str() {
+            "json" => Some(Self::Json),
+            "md" | "markdown" => Some(Self::Markdown),
+            "txt" => Some(Self::Text),
+            "rss" | "atom" => Some(Self::Feed),
+            _ => None,
+        }
+    }
+
+    fn sniff(content: &str) -> Self {
+        let trimmed = content.trim_start();
+        if trimmed.starts_with(['{', '['])
+            && serde_json::from_str::<serde_json::Value>(content).is_ok()
+        {
+            Self::Json
+        } else if is_feed(content) {
+            Self::Feed
+        } else {
+            Self::Html
+        }
+    }
+}
+
 fn add_additional_lines(content: String, spans: &[Span]) -> String {
     let mut max_height = 0;
     spans
@@ -223,12 +397,14 @@ fn resize_text(content: &str, size: Option<Size>) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::search_engine::feed::feed_for;
     use crate::search_engine::link::tests::TEST_CONFIG;
     use crate::search_engine::link::File;
     use crate::search_engine::link::HtmlSource::FileSource;
     use ctor::ctor;
     use globset::GlobSet;
     use std::collections::HashMap;
+    use std::fs;
     use std::path::Path;
 
     impl PageExtractor {
@@ -400,4 +576,124 @@ mod tests {
             .len();
         assert_eq!(length, 271);
     }
+
+    #[test]
+    fn test_file_uses_meta_charset() {
+        let (title, text) = text_for_file("tests/data/windows1251.html");
+
+        assert_eq!(title, "Документация");
+        assert!(text.iter().any(|line| line.contains("Привет, мир")));
+    }
+
+    fn text_for_file(path: &str) -> (String, Vec<String>) {
+        let file = File::new(String::from(path), String::new());
+        let config = ExtractionConfig::new(
+            ColorMode::Tui,
+            vec![],
+            HashMap::new(),
+            Some("body".to_string()),
+            GlobSet::empty(),
+            vec![],
+            true,
+        );
+        let (title, text) = PageExtractor::test_init(config).get_tui_text(&FileSource(file));
+        (title, text.iter().map(Line::content).collect())
+    }
+
+    #[test]
+    fn test_json_is_pretty_printed() {
+        let (title, text) = text_for_file("tests/data/sample.json");
+
+        assert_eq!(title, "sample.json");
+        assert_eq!(
+            text,
+            vec![
+                "{",
+                "  \"name\": \"is-fast\",",
+                "  \"tags\": [",
+                "    \"cli\",",
+                "    \"tui\"",
+                "  ],",
+                "  \"stars\": 5,",
+                "  \"archived\": false",
+                "}",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plain_text_is_unchanged() {
+        let (title, text) = text_for_file("tests/data/sample.txt");
+
+        assert_eq!(title, "sample.txt");
+        assert_eq!(
+            text,
+            vec!["Plain text is shown as it is.", "    <b>Even markup</b>"]
+        );
+    }
+
+    #[test]
+    fn test_markdown_is_formatted() {
+        let (title, text) = text_for_file("tests/data/sample.md");
+
+        assert_eq!(title, "Sample Notes");
+        assert!(text.iter().any(|line| line.contains("Sample Notes")));
+        assert!(text
+            .iter()
+            .any(|line| line.contains("Some bold text with a link.")));
+        assert!(text.iter().any(|line| line.contains("First item")));
+        assert!(text.iter().any(|line| line == "fn main() {}"));
+        assert!(!text
+            .iter()
+            .any(|line| line.contains("**") || line.contains("```")));
+    }
+
+    #[test]
+    fn test_feed_entries_are_listed() {
+        let (title, text) = text_for_file("tests/data/feed.xml");
+
+        assert_eq!(title, "is-fast releases");
+        assert!(text.iter().any(|line| line.contains("v0.16.0")));
+        assert!(text.iter().any(|line| line
+            .contains("2025-06-01 https://github.com/Magic-JD/is-fast/releases/tag/v0.16.0")));
+        assert!(text.iter().any(|line| line.contains("Adds feed viewing.")));
+        let source = FileSource(File::new(
+            String::from("tests/data/feed.xml"),
+            String::new(),
+        ));
+        let feed = feed_for(&source).expect("the feed should be recorded");
+        assert_eq!(feed.entries.len(), 2);
+        assert_eq!(feed.entries[1].published.as_deref(), Some("2025-05-01"));
+    }
+
+    #[test]
+    fn test_content_kind_from_media_type() {
+        let url = "https://raw.example.com/repo/README.md";
+        assert_eq!(
+            ContentKind::from_media_type("application/problem+json", url, ""),
+            ContentKind::Json
+        );
+        assert_eq!(
+            ContentKind::from_media_type("text/plain", url, ""),
+            ContentKind::Markdown
+        );
+        assert_eq!(
+            ContentKind::from_media_type("text/plain", "https://example.com/notes", ""),
+            ContentKind::Text
+        );
+        assert_eq!(
+            ContentKind::from_media_type("text/html", url, ""),
+            ContentKind::Html
+        );
+        assert_eq!(ContentKind::sniff("[1, 2]"), ContentKind::Json);
+        assert_eq!(ContentKind::sniff("[link] text"), ContentKind::Html);
+        assert_eq!(
+            ContentKind::from_media_type("application/atom+xml", url, ""),
+            ContentKind::Feed
+        );
+        assert_eq!(
+            ContentKind::from_media_type("text/xml", url, "<rss><channel></channel></rss>"),
+            ContentKind::Feed
+        );
+    }
 }
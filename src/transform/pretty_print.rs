@@ -1,5 +1,6 @@
 use crate::cli::command::ColorMode;
 use crate::config::load::Config;
+use crate::search_engine::link::Link;
 use crate::DisplayConfig;
 use nu_ansi_term::Style;
 use textwrap::{fill, Options, WrapAlgorithm};
@@ -7,6 +8,7 @@ use textwrap::{fill, Options, WrapAlgorithm};
 pub fn conditional_formatting(
     page_title: &str,
     mut content: String,
+    search_result: Option<&Link>,
     display_configuration_list: &[DisplayConfig],
 ) -> String {
     // Trim content and remove unnecessary leading spaces.
@@ -19,7 +21,7 @@ pub fn conditional_formatting(
 
     let mut width = terminal_width();
 
-    let (mut margin, mut wrap, mut title) = (0, false, None);
+    let (mut margin, mut wrap, mut title, mut result) = (0, false, None, false);
     for display_config in display_configuration_list {
         match display_config {
             DisplayConfig::Margin(amount) => {
@@ -28,9 +30,14 @@ pub fn conditional_formatting(
             }
             DisplayConfig::Wrap => wrap = true,
             DisplayConfig::Title(title_val) => title = Some(title_val),
+            DisplayConfig::Result => result = true,
         }
     }
 
+    if let Some(summary) = search_result.filter(|_| result).and_then(result_summary) {
+        content.insert_str(0, &format!("{summary}\n\n"));
+    }
+
     if let Some(title_val_option) = title {
         let mut title = page_title;
         if let Some(user_title) = title_val_option {
@@ -63,6 +70,20 @@ pub fn conditional_formatting(
     format!("\n{content}\n")
 }
 
+// Describes the search result the page was opened from, as given by the search engine.
+fn result_summary(link: &Link) -> Option<String> {
+    let engine = link.engine.as_ref()?;
+    let mut lines = vec![];
+    if let Some(title) = &link.title {
+        lines.push(title.clone());
+    }
+    lines.push(format!("{} (via {engine})", link.url));
+    if let Some(snippet) = &link.snippet {
+        lines.push(snippet.clone());
+    }
+    Some(lines.join("\n"))
+}
+
 #[cfg(not(test))]
 fn terminal_width() -> u16 {
     use crossterm::terminal::size;
@@ -82,6 +103,7 @@ fn terminal_width() -> u16 {
 
 #[cfg(test)]
 mod tests {
+    use crate::search_engine::link::Link;
     use crate::transform::pretty_print::conditional_formatting;
     use crate::DisplayConfig;
     use std::fs;
@@ -98,7 +120,7 @@ mod tests {
         let expected_output = read_file_content("unformatted_result.txt");
 
         let display_config = vec![]; // No display config for this case
-        let result = conditional_formatting("Title", input, &display_config);
+        let result = conditional_formatting("Title", input, None, &display_config);
         assert_eq!(result, expected_output);
     }
 
@@ -108,7 +130,7 @@ mod tests {
         let expected_output = read_file_content("test_output_title.txt");
 
         let display_config = vec![DisplayConfig::Title(Some("Test Title".to_string()))];
-        let result = conditional_formatting("Title", input, &display_config);
+        let result = conditional_formatting("Title", input, None, &display_config);
         assert_eq!(result, expected_output);
     }
 
@@ -118,7 +140,7 @@ mod tests {
         let expected_output = read_file_content("test_output_title.txt");
 
         let display_config = vec![DisplayConfig::Title(None)];
-        let result = conditional_formatting("Test Title", input, &display_config);
+        let result = conditional_formatting("Test Title", input, None, &display_config);
         assert_eq!(result, expected_output);
     }
 
@@ -128,7 +150,7 @@ mod tests {
         let expected_output = read_file_content("test_output_margin_wrap.txt");
 
         let display_config = vec![DisplayConfig::Margin(4), DisplayConfig::Wrap];
-        let result = conditional_formatting("Title", input, &display_config);
+        let result = conditional_formatting("Title", input, None, &display_config);
         assert_eq!(result, expected_output);
     }
 
@@ -142,7 +164,43 @@ mod tests {
             DisplayConfig::Margin(5),
             DisplayConfig::Wrap,
         ];
-        let result = conditional_formatting("", input, &display_config);
+        let result = conditional_formatting("", input, None, &display_config);
         assert_eq!(result, expected_output);
     }
+
+    #[test]
+    fn test_conditional_formatting_with_result() {
+        let link = Link::from_search(
+            "https://www.rust-lang.org/",
+            "Rust Programming Language",
+            "A language empowering everyone.",
+            "duckduckgo",
+        );
+
+        let display_config = vec![DisplayConfig::Result];
+        let result = conditional_formatting(
+            "Title",
+            String::from("Content"),
+            Some(&link),
+            &display_config,
+        );
+        assert_eq!(
+            result,
+            "\nRust Programming Language\nhttps://www.rust-lang.org/ (via duckduckgo)\nA language empowering everyone.\n\nContent\n"
+        );
+    }
+
+    #[test]
+    fn test_conditional_formatting_result_ignored_for_direct_links() {
+        let link = Link::new("https://www.rust-lang.org/");
+
+        let display_config = vec![DisplayConfig::Result];
+        let result = conditional_formatting(
+            "Title",
+            String::from("Content"),
+            Some(&link),
+            &display_config,
+        );
+        assert_eq!(result, "\nContent\n");
+    }
 }
@@ -7,7 +7,7 @@ use crossterm::terminal::{
 use ratatui::backend::CrosstermBackend;
 use ratatui::layout::Rect;
 use ratatui::text::Text;
-use ratatui::widgets::{Block, Paragraph, Table, TableState};
+use ratatui::widgets::{Block, Paragraph, Table, TableState, Wrap};
 use ratatui::{Frame, Terminal};
 use std::io::{stdout, Stdout};
 use std::sync::{Mutex, MutexGuard};
@@ -84,6 +84,19 @@ impl Display {
             .unwrap_or_else(|err| self.shutdown_with_error(&err.to_string()));
     }
 
+    pub fn loading_page(&mut self, title: &str, description: &str) {
+        let block = default_block(&format!(" Loading {title} "), "");
+        let paragraph = Paragraph::new(Text::from(description.to_string()))
+            .block(block)
+            .wrap(Wrap { trim: false });
+        self.unwrap_terminal()
+            .draw(|frame| {
+                let size = frame.area();
+                frame.render_widget(paragraph, size);
+            })
+            .unwrap_or_else(|err| self.shutdown_with_error(&err.to_string()));
+    }
+
     pub fn render(&mut self, drawables: Vec<Widget>) {
         self.unwrap_terminal()
             .draw(|frame| {
@@ -94,7 +94,7 @@ impl HistoryWidgetGenerator {
     }
 }
 
-fn highlight_text(plain_text: String, user_search: &str) -> Line<'static> {
+pub(crate) fn highlight_text(plain_text: String, user_search: &str) -> Line<'static> {
     let user_search = user_search.replace(' ', "");
     if user_search.is_empty() || plain_text.is_empty() {
         return Line::from(plain_text);
@@ -167,7 +167,7 @@ fn searched_on_to_string(search_on: &SearchOn) -> String {
     }
 }
 
-fn count_result_text(row_count: u16) -> String {
+pub(crate) fn count_result_text(row_count: u16) -> String {
     if row_count == 1 {
         format!("{row_count} result ")
     } else {
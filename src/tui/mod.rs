@@ -4,3 +4,5 @@ pub mod history_content;
 mod history_widgets;
 pub mod page_content;
 mod page_widgets;
+pub mod results_content;
+mod results_widgets;
@@ -10,6 +10,8 @@ use ratatui::text::Text as RText;
 use ratatui::widgets::{Block as RBlock, Paragraph as RParagraph};
 
 static PAGE_INSTRUCTIONS: &str = " Quit: q/Esc | Scroll Down: j/↓ | Scroll Up: k/↑ | Page Down: CTRL+d/PgDn | Page Up: CTRL+u/PgUp | Next: n/→ | Back: b/← | Open in Browser: o ";
+static RESULTS_INSTRUCTIONS: &str = "| Results: r ";
+static MORE_INSTRUCTIONS: &str = "| More: m ";
 static TUI_MARGIN: Lazy<u16> = Lazy::new(Config::get_page_margin);
 
 pub struct PageContent<'a> {
@@ -18,16 +20,31 @@ pub struct PageContent<'a> {
     areas: (Rect, Rect, Rect),
     index: usize,
     scroll: u16,
+    instructions: String,
 }
 
 impl PageContent<'_> {
-    pub fn new(pages: &[HtmlSource], available_space: Rect) -> Self {
+    pub fn new(
+        pages: &[HtmlSource],
+        index: usize,
+        from_results: bool,
+        can_load_more: bool,
+        available_space: Rect,
+    ) -> Self {
         let total_area = available_space;
         let areas = PageContent::page_area(available_space);
-        let index = 0;
         let scroll = 0;
+        let instructions = format!(
+            "{PAGE_INSTRUCTIONS}{}{}",
+            if from_results {
+                RESULTS_INSTRUCTIONS
+            } else {
+                ""
+            },
+            if can_load_more { MORE_INSTRUCTIONS } else { "" }
+        );
         let (title, page) = new_page(index, pages);
-        let border = default_block(&title, PAGE_INSTRUCTIONS);
+        let border = default_block(&title, &instructions);
         let page_numbers = draw_page_numbers(index + 1, pages.len());
         let widgets = (border, page, page_numbers);
         PageContent {
@@ -36,6 +53,7 @@ impl PageContent<'_> {
             areas,
             index,
             scroll,
+            instructions,
         }
     }
 
@@ -53,7 +71,7 @@ impl PageContent<'_> {
         if index != self.index {
             self.index = index;
             let (title, page) = new_page(index, pages);
-            let border = default_block(&title, PAGE_INSTRUCTIONS);
+            let border = default_block(&title, &self.instructions);
             let page_numbers = draw_page_numbers(index + 1, pages.len());
             self.widgets = (border, page, page_numbers);
         }
@@ -2,6 +2,7 @@ use crate::config::load::Config;
 use crate::database::history_database::add_history;
 use crate::search_engine::link::HtmlSource;
 use crate::search_engine::link::HtmlSource::LinkSource;
+use crate::search_engine::link::Link;
 use crate::transform::cache::{get_content, preload};
 use crate::tui::general_widgets::TUI_BORDER_COLOR;
 use ratatui::layout::Alignment;
@@ -21,15 +22,15 @@ pub fn new_page(index: usize, sources: &[HtmlSource]) -> (String, Paragraph<'sta
         },
         |source| {
             let (title, paragraph) = get_content(source);
-            let url = source.get_url();
+            let url = source.get_final_url();
             if *Config::get_history_enabled() {
                 if let LinkSource(_) = source {
-                    add_history(&title, url).unwrap_or_else(|err| {
+                    add_history(&title, &url).unwrap_or_else(|err| {
                         log::error!("Failed to add history for page {title} ({url}) {err}");
                     });
                 }
             }
-            let display_title = format!(" {title} ({url}) ");
+            let display_title = display_title(&title, &url, source);
             let formatted_paragraph = paragraph
                 .style(Style::default().fg(Color::White))
                 .wrap(Wrap { trim: false })
@@ -39,6 +40,22 @@ pub fn new_page(index: usize, sources: &[HtmlSource]) -> (String, Paragraph<'sta
     )
 }
 
+// Pages opened from a search show the engine that found them, and the snippet it gave, so the
+// result can still be recognised once the page has loaded.
+fn display_title(title: &str, url: &str, source: &HtmlSource) -> String {
+    match source.get_link() {
+        Some(Link {
+            engine: Some(engine),
+            snippet,
+            ..
+        }) => match snippet {
+            Some(snippet) => format!(" {title} ({url}) [{engine}] - {snippet} "),
+            None => format!(" {title} ({url}) [{engine}] "),
+        },
+        _ => format!(" {title} ({url}) "),
+    }
+}
+
 pub fn draw_page_numbers(index: usize, pages: usize) -> Text<'static> {
     Text::from(Line::styled(
         format!(" [{index}/{pages}] "),
@@ -46,3 +63,31 @@ pub fn draw_page_numbers(index: usize, pages: usize) -> Text<'static> {
     ))
     .alignment(Alignment::Right)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_title_shows_search_result() {
+        let source = LinkSource(Link::from_search(
+            "https://www.rust-lang.org",
+            "Rust",
+            "A language empowering everyone",
+            "duckduckgo",
+        ));
+
+        assert_eq!(
+            display_title("Rust", "https://www.rust-lang.org", &source),
+            " Rust (https://www.rust-lang.org) [duckduckgo] - A language empowering everyone "
+        );
+        assert_eq!(
+            display_title(
+                "Rust",
+                "https://www.rust-lang.org",
+                &LinkSource(Link::new("https://www.rust-lang.org"))
+            ),
+            " Rust (https://www.rust-lang.org) "
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use crate::config::load::Config;
+use crate::search_engine::link::HtmlSource;
+use crate::tui::display::Widget;
+use crate::tui::display::Widget::{Block, Paragraph, Table, Text};
+use crate::tui::general_widgets::default_block;
+use crate::tui::results_widgets::ResultsWidgetGenerator;
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Matcher, Utf32Str};
+use once_cell::sync::Lazy;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::text::Text as RText;
+use ratatui::widgets::TableState;
+use ratatui::widgets::{Block as RBlock, Paragraph as RParagraph, Table as RTable};
+
+pub(crate) static RESULTS_INSTRUCTIONS: &str =
+    " Quit: Esc | Scroll Down: ↓ | Scroll Up: ↑ | Open: ↵ | Type to filter ";
+
+static SEARCH_TYPE: Lazy<&AtomKind> = Lazy::new(Config::get_search_type);
+
+static RESULTS_WIDGET_GENERATOR: Lazy<ResultsWidgetGenerator> =
+    Lazy::new(ResultsWidgetGenerator::new);
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ResultEntry {
+    pub(crate) index: usize,
+    pub(crate) title: String,
+    pub(crate) url: String,
+    pub(crate) snippet: String,
+    pub(crate) date: String,
+}
+
+impl ResultEntry {
+    fn from_source(index: usize, source: &HtmlSource) -> Self {
+        let link = source.get_link();
+        Self {
+            index,
+            title: link.and_then(|link| link.title.clone()).unwrap_or_default(),
+            url: source.get_url().to_string(),
+            snippet: link
+                .and_then(|link| link.snippet.clone())
+                .unwrap_or_default(),
+            date: link
+                .and_then(|link| link.published.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn searchable_text(&self) -> String {
+        format!("{} {} {}", self.title, self.url, self.snippet)
+    }
+}
+
+pub struct ResultsContent<'a> {
+    total_area: Rect,
+    widgets: (RBlock<'a>, RTable<'a>, RParagraph<'a>, RText<'a>),
+    areas: (Rect, Rect, Rect, Rect),
+    current_results: Vec<ResultEntry>,
+    all_results: Vec<ResultEntry>,
+    search_term: String,
+    pub(crate) table_state: TableState,
+    needs_update: bool,
+    feed: bool,
+}
+
+impl ResultsContent<'_> {
+    pub fn new(pages: &[HtmlSource], total_area: Rect) -> Self {
+        Self::create(pages, " Results ", false, total_area)
+    }
+
+    /// The entries of a feed, shown with the date they were published rather than their url.
+    pub fn for_feed(feed_title: &str, entries: &[HtmlSource], total_area: Rect) -> Self {
+        Self::create(entries, &format!(" {feed_title} "), true, total_area)
+    }
+
+    fn create(pages: &[HtmlSource], title: &str, feed: bool, total_area: Rect) -> Self {
+        let all_results = pages
+            .iter()
+            .enumerate()
+            .map(|(index, source)| ResultEntry::from_source(index, source))
+            .collect::<Vec<ResultEntry>>();
+        let current_results = all_results.clone();
+        let block = default_block(title, RESULTS_INSTRUCTIONS);
+        let table = RESULTS_WIDGET_GENERATOR.create_table(&current_results, "", feed);
+        let search = RESULTS_WIDGET_GENERATOR.draw_search_text("");
+        let row_count = RESULTS_WIDGET_GENERATOR.draw_results_count(current_results.len() as u16);
+        let widgets = (block, table, search, row_count);
+        let areas = Self::results_areas(total_area);
+        let mut table_state = TableState::default();
+        table_state.select_first();
+        Self {
+            total_area,
+            widgets,
+            areas,
+            current_results,
+            all_results,
+            search_term: String::new(),
+            table_state,
+            needs_update: false,
+            feed,
+        }
+    }
+
+    pub fn create_widgets(&mut self, available_space: Rect) -> Vec<Widget<'_>> {
+        if available_space != self.total_area {
+            self.total_area = available_space;
+            self.areas = Self::results_areas(available_space);
+        }
+        if self.needs_update {
+            self.widgets.1 = RESULTS_WIDGET_GENERATOR.create_table(
+                &self.current_results,
+                &self.search_term,
+                self.feed,
+            );
+            self.widgets.2 = RESULTS_WIDGET_GENERATOR.draw_search_text(&self.search_term);
+            self.widgets.3 =
+                RESULTS_WIDGET_GENERATOR.draw_results_count(self.current_results.len() as u16);
+            self.needs_update = false;
+        }
+        let (block, table, search, row_count) = &self.widgets;
+        let (border_area, table_area, search_area, count_row_area) = &self.areas;
+        vec![
+            Block(block, border_area),
+            Table(table, &mut self.table_state, table_area),
+            Paragraph(search, search_area),
+            Text(row_count, count_row_area),
+        ]
+    }
+
+    /// The index in the original page list of the currently selected result.
+    pub(crate) fn selected_page(&self) -> Option<usize> {
+        self.table_state
+            .selected()
+            .and_then(|selected| self.current_results.get(selected))
+            .map(|entry| entry.index)
+    }
+
+    pub(crate) fn add_char(&mut self, c: char) {
+        self.search_term.push(c);
+        self.filter();
+    }
+
+    pub(crate) fn remove_char(&mut self) {
+        self.search_term.pop();
+        self.filter();
+    }
+
+    pub(crate) fn scroll_up(&mut self) {
+        if let Some(selected) = self.table_state.selected() {
+            self.table_state.select(Some(selected.saturating_sub(1)));
+        }
+    }
+
+    pub(crate) fn scroll_down(&mut self) {
+        if let Some(selected) = self.table_state.selected() {
+            self.table_state.select(Some(
+                selected
+                    .saturating_add(1)
+                    .min(self.current_results.len().saturating_sub(1)),
+            ));
+        }
+    }
+
+    fn filter(&mut self) {
+        self.current_results = order_by_match(&self.all_results, &self.search_term);
+        *self.table_state.offset_mut() = 0;
+        self.table_state.select_first();
+        self.needs_update = true;
+    }
+
+    fn results_areas(size: Rect) -> (Rect, Rect, Rect, Rect) {
+        let areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(1),
+                    Constraint::Min(1),
+                    Constraint::Length(2),
+                ]
+                .as_ref(),
+            )
+            .split(size);
+        let search_bar_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(90), Constraint::Percentage(10)].as_ref())
+            .split(areas[2]);
+        (size, areas[1], search_bar_layout[0], search_bar_layout[1])
+    }
+}
+
+// Unlike the history the results keep the engine ranking, which is used to break ties.
+fn order_by_match(results: &[ResultEntry], user_search: &str) -> Vec<ResultEntry> {
+    if user_search.is_empty() {
+        return results.to_vec();
+    }
+    let mut matcher = Matcher::new(nucleo_matcher::Config::DEFAULT);
+    let pattern = Pattern::new(
+        user_search,
+        CaseMatching::Ignore,
+        Normalization::Smart,
+        **SEARCH_TYPE,
+    );
+    let mut entry_2_score = results
+        .iter()
+        .filter_map(|entry| {
+            pattern
+                .score(
+                    Utf32Str::new(&entry.searchable_text(), &mut vec![]),
+                    &mut matcher,
+                )
+                .map(|score| (entry, score))
+        })
+        .collect::<Vec<(&ResultEntry, u32)>>();
+    entry_2_score.sort_by(|(e1, a), (e2, b)| b.cmp(a).then(e1.index.cmp(&e2.index)));
+    entry_2_score
+        .into_iter()
+        .map(|(entry, _)| entry.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(index: usize, title: &str, url: &str) -> ResultEntry {
+        ResultEntry {
+            index,
+            title: title.to_string(),
+            url: url.to_string(),
+            snippet: String::new(),
+            date: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_order_by_match_keeps_rank_without_search() {
+        let results = vec![
+            entry(0, "Rust", "https://rust-lang.org"),
+            entry(1, "Java", "https://java.com"),
+        ];
+
+        assert_eq!(order_by_match(&results, ""), results);
+    }
+
+    #[test]
+    fn test_order_by_match_filters_and_ranks() {
+        let results = vec![
+            entry(0, "Java is the best", "https://java.com"),
+            entry(1, "R U S T is great", "https://example.com"),
+            entry(2, "The Rust Book", "https://doc.rust-lang.org/book"),
+            entry(
+                3,
+                "Rust by Example",
+                "https://doc.rust-lang.org/rust-by-example",
+            ),
+        ];
+
+        let ordered = order_by_match(&results, "rust book");
+
+        assert_eq!(ordered.len(), 1);
+        assert_eq!(ordered[0].index, 2);
+
+        let ordered = order_by_match(&results, "rust");
+        assert_eq!(ordered.len(), 3);
+        assert!(ordered.iter().all(|entry| entry.index != 0));
+    }
+}
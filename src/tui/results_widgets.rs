@@ -0,0 +1,99 @@
+use crate::config::load::Config as IsConfig;
+use crate::config::load::HistoryWidgetConfig;
+use crate::tui::general_widgets::TUI_BORDER_COLOR;
+use crate::tui::history_widgets::{count_result_text, highlight_text};
+use crate::tui::results_content::ResultEntry;
+use ratatui::layout::{Alignment, Constraint};
+use ratatui::prelude::{Line, Modifier, Style, Text};
+use ratatui::widgets::{Cell, Paragraph, Row, Table};
+
+// The results list shares its colours with the history list.
+pub struct ResultsWidgetGenerator {
+    config: HistoryWidgetConfig,
+}
+
+impl ResultsWidgetGenerator {
+    pub fn new() -> Self {
+        Self {
+            config: IsConfig::get_history_widget_config(),
+        }
+    }
+
+    // Feed entries show when they were published, as the url is the same site for every entry.
+    pub fn create_table<'a>(
+        &self,
+        results: &[ResultEntry],
+        user_search: &str,
+        feed: bool,
+    ) -> Table<'a> {
+        let widths = if feed {
+            [
+                Constraint::Percentage(40),
+                Constraint::Length(10),
+                Constraint::Fill(1),
+            ]
+        } else {
+            [
+                Constraint::Percentage(35),
+                Constraint::Percentage(25),
+                Constraint::Percentage(40),
+            ]
+        };
+        Table::from_iter(self.create_rows(results, user_search, feed))
+            .widths(widths)
+            .column_spacing(1)
+            .highlight_symbol("> ")
+            .row_highlight_style(Style::default().add_modifier(Modifier::BOLD))
+    }
+
+    fn create_rows(
+        &self,
+        results: &[ResultEntry],
+        user_search: &str,
+        feed: bool,
+    ) -> Vec<Row<'static>> {
+        results
+            .iter()
+            .map(|result| {
+                let title = if result.title.is_empty() {
+                    result.url.clone()
+                } else {
+                    result.title.clone()
+                };
+                let detail = if feed {
+                    Cell::from(result.date.clone())
+                        .style(self.config.get_time_style().to_rat_style())
+                } else {
+                    Cell::from(result.url.clone()).style(self.config.get_url_style().to_rat_style())
+                };
+                Row::new(vec![
+                    Cell::from(highlight_text(title, user_search))
+                        .style(self.config.get_title_style().to_rat_style()),
+                    detail,
+                    Cell::from(result.snippet.clone())
+                        .style(self.config.get_time_style().to_rat_style()),
+                ])
+            })
+            .collect()
+    }
+
+    pub fn draw_search_text<'a>(&self, user_input: &str) -> Paragraph<'a> {
+        Paragraph::new(
+            Line::from(format!(" [FILTER] {user_input}")).style(
+                self.config
+                    .get_text_style()
+                    .to_rat_style()
+                    .add_modifier(Modifier::BOLD),
+            ),
+        )
+    }
+
+    pub fn draw_results_count(&self, row_count: u16) -> Text<'static> {
+        Text::from(vec![
+            Line::default(), // Move to the bottom line
+            Line::from(count_result_text(row_count))
+                .style(TUI_BORDER_COLOR.add_modifier(Modifier::BOLD))
+                .alignment(Alignment::Right),
+        ])
+    }
+}